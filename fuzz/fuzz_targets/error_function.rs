@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto_math::{ErrorFunctionDecimal, ErrorFunctionPreciseDecimal};
+use scrypto_math_fuzz::Input;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input::new(data);
+    let x = input.decimal();
+    let _ = x.erf();
+    let _ = x.erfc();
+    let x = input.precise_decimal();
+    let _ = x.erf();
+    let _ = x.erfc();
+});
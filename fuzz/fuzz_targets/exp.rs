@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto_math::{ExponentialDecimal, ExponentialPreciseDecimal};
+use scrypto_math_fuzz::Input;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input::new(data);
+    let x = input.decimal();
+    let _ = x.exp();
+    let x = input.precise_decimal();
+    let _ = x.exp();
+});
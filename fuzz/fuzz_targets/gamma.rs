@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto_math::{GammaDecimal, GammaPreciseDecimal};
+use scrypto_math_fuzz::Input;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input::new(data);
+    let (x, y) = (input.decimal(), input.decimal());
+    let _ = x.gamma();
+    let _ = x.ln_gamma();
+    let _ = x.beta(y);
+    let (x, y) = (input.precise_decimal(), input.precise_decimal());
+    let _ = x.gamma();
+    let _ = x.ln_gamma();
+    let _ = x.beta(y);
+});
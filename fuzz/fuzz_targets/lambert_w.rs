@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto_math::{LambertWDecimal, LambertWPreciseDecimal};
+use scrypto_math_fuzz::Input;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input::new(data);
+    let x = input.decimal();
+    let _ = x.lambert_w0();
+    let _ = x.lambert_wm1();
+    let x = input.precise_decimal();
+    let _ = x.lambert_w0();
+    let _ = x.lambert_wm1();
+});
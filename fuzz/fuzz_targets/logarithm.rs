@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto_math::{LogarithmDecimal, LogarithmPreciseDecimal};
+use scrypto_math_fuzz::Input;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input::new(data);
+    let (x, y) = (input.decimal(), input.decimal());
+    let _ = x.ln();
+    let _ = x.log2();
+    let _ = x.log10();
+    let _ = x.log_base(y);
+    let (x, y) = (input.precise_decimal(), input.precise_decimal());
+    let _ = x.ln();
+    let _ = x.log2();
+    let _ = x.log10();
+    let _ = x.log_base(y);
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto_math::{LogisticDecimal, LogisticPreciseDecimal};
+use scrypto_math_fuzz::Input;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input::new(data);
+    let x = input.decimal();
+    let _ = x.sigmoid();
+    let _ = x.softplus();
+    let x = input.precise_decimal();
+    let _ = x.sigmoid();
+    let _ = x.softplus();
+});
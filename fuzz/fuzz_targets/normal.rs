@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto_math::{NormalDistributionDecimal, NormalDistributionPreciseDecimal};
+use scrypto_math_fuzz::Input;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input::new(data);
+    let x = input.decimal();
+    let _ = x.normal_pdf();
+    let _ = x.normal_cdf();
+    let _ = x.normal_inverse_cdf();
+    let x = input.precise_decimal();
+    let _ = x.normal_pdf();
+    let _ = x.normal_cdf();
+    let _ = x.normal_inverse_cdf();
+});
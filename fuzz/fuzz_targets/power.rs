@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto_math::{PowerDecimal, PowerPreciseDecimal};
+use scrypto_math_fuzz::Input;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input::new(data);
+    let (x, y) = (input.decimal(), input.decimal());
+    let _ = x.pow(y);
+    let (x, y) = (input.precise_decimal(), input.precise_decimal());
+    let _ = x.pow(y);
+});
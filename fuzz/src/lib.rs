@@ -0,0 +1,57 @@
+//! Decoding of fuzzer input into Decimals and PreciseDecimals
+//!
+//! Every value starts with a mode byte: even modes take the full range of the subunits, odd modes
+//! a 64 bit integer scaled by a power of ten, which covers the ranges where the functions do not
+//! simply overflow or underflow.
+
+use radix_common::math::{Decimal, PreciseDecimal, I192, I256};
+
+fn power_of_ten<T: From<u8> + std::ops::Mul<Output = T>>(exponent: u32) -> T {
+    (0..exponent).fold(T::from(1), |power, _| power * T::from(10))
+}
+
+pub struct Input<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Input<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Takes the next `n` bytes, padded with zeros once the input is exhausted
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        let n = N.min(self.data.len());
+        bytes[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        bytes
+    }
+
+    /// 64 bit integer scaled by 10^-(0..=decimals)
+    fn scaled(&mut self, decimals: u8) -> (i64, u32) {
+        let [scale] = self.take::<1>();
+        let value = i64::from_le_bytes(self.take::<8>());
+        (value, u32::from(scale % (decimals + 1)))
+    }
+
+    pub fn decimal(&mut self) -> Decimal {
+        let [mode] = self.take::<1>();
+        if mode % 2 == 0 {
+            return Decimal::from_attos(I192::from_le_bytes(&self.take::<24>()));
+        }
+        let (value, decimals) = self.scaled(18);
+        let attos = I192::from(value) * power_of_ten::<I192>(18 - decimals);
+        Decimal::from_attos(attos)
+    }
+
+    pub fn precise_decimal(&mut self) -> PreciseDecimal {
+        let [mode] = self.take::<1>();
+        if mode % 2 == 0 {
+            return PreciseDecimal::from_precise_subunits(I256::from_le_bytes(&self.take::<32>()));
+        }
+        let (value, decimals) = self.scaled(36);
+        let subunits = I256::from(value) * power_of_ten::<I256>(36 - decimals);
+        PreciseDecimal::from_precise_subunits(subunits)
+    }
+}
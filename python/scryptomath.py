@@ -1,6 +1,9 @@
 from abc import abstractmethod
-from typing import Union
+from typing import Optional, Union
 import decimal
+import json
+import random
+import sys
 from decimal import localcontext
 
 
@@ -114,11 +117,89 @@ class ScryptoBaseDecimal(FixedBaseDecimal):
         scrypto_type = self.__class__.__name__
         return f"{scrypto_type}(I{self.bits}::from_digits({self.to_digits()}))"
 
+    def _subunits(self) -> int:
+        """
+        Subunits of 10^-36 like `PreciseDecimal::precise_subunits`, exact for both.
+        """
+        context = decimal.Context(prec=200)
+        return int(self.scaleb(PreciseDecimal.decimal_places, context))
+
+    @classmethod
+    def _from_subunits(cls, subunits: int):
+        """
+        Narrows subunits of 10^-36 to this type truncating towards zero like Scrypto.
+        """
+        scale = 10 ** (PreciseDecimal.decimal_places - cls.decimal_places)
+        value = _truncating_div(subunits, scale)
+        if not -(2 ** (cls.bits - 1)) <= value < 2 ** (cls.bits - 1):
+            return None
+        context = decimal.Context(prec=200)
+        return cls(decimal.Decimal(value).scaleb(-cls.decimal_places, context))
+
+    def _scrypto(self, function, *args):
+        try:
+            subunits = [PreciseDecimal(arg)._subunits() for arg in (self, *args)]
+            return self._from_subunits(function(*subunits))
+        except _Overflow:
+            return None
+
+    def scrypto_exp(self):
+        """
+        Bit exact result of `ExponentialPreciseDecimal::exp` narrowed to this type,
+        None on overflow.
+        """
+        return self._scrypto(_exp)
+
+    def scrypto_ln(self):
+        """
+        Bit exact result of `LogarithmPreciseDecimal::ln` narrowed to this type,
+        None if not positive.
+        """
+        return self._scrypto(_ln)
+
+    def scrypto_log2(self):
+        """
+        Bit exact result of `LogarithmPreciseDecimal::log2` narrowed to this type,
+        None if not positive.
+        """
+        return self._scrypto(_log2)
+
+    def scrypto_log10(self):
+        """
+        Bit exact result of `LogarithmPreciseDecimal::log10` narrowed to this type,
+        None if not positive.
+        """
+        return self._scrypto(_log10)
+
+    def scrypto_log_base(self, base: decimal.Decimal):
+        """
+        Bit exact result of `LogarithmPreciseDecimal::log_base` narrowed to this type,
+        None if not positive or the base is 1.
+        """
+        return self._scrypto(_log_base, base)
+
+    def scrypto_pow(self, exp: decimal.Decimal):
+        """
+        Bit exact result of `PowerPreciseDecimal::pow` narrowed to this type,
+        None if undefined.
+        """
+        return self._scrypto(_pow, exp)
+
 
 class Decimal(ScryptoBaseDecimal):
     decimal_places = 18
     bits = 192
 
+    def scrypto_exp(self):
+        """
+        Bit exact result of `ExponentialDecimal::exp`, None on overflow.
+        """
+        if self < -42:
+            return Decimal(0)
+        if self > 90:
+            return None
+        return super().scrypto_exp()
+
 
 class PreciseDecimal(ScryptoBaseDecimal):
     decimal_places = 36
@@ -131,6 +212,207 @@ class PreciseDecimal(ScryptoBaseDecimal):
         return Decimal._cast(self)
 
 
+# Bit exact mirror of the Scrypto implementation of exp, ln, log2, log10, log_base and
+# pow
+#
+# Every function works on the subunits of 10^-36 of a PreciseDecimal, truncating
+# products and quotients towards zero through a 384 bit intermediate result, exactly
+# like `CheckedMul` and `CheckedDiv` of PreciseDecimal. Results outside of the range of
+# I256 raise _Overflow, which the `scrypto_*` methods turn into None.
+
+
+class _Overflow(ArithmeticError):
+    pass
+
+
+def _pdec(value: str) -> int:
+    return PreciseDecimal(value)._subunits()
+
+
+_ONE = 10**36
+_LN2 = _pdec("0.693147180559945309417232121458176568")
+_LN10 = _pdec("2.302585092994045684017991454684364207")
+_INVLN2 = _pdec("1.442695040888963407359924681001892137")
+_HALF = _pdec("0.5")
+_TWO = 2 * _ONE
+_SQRT = _pdec("1.414213562373095048801688724209698078")
+_SQRT_HALF = _pdec("0.707106781186547524400844362104849039")
+_P = [
+    _pdec("0.166666666666666019037"),
+    _pdec("-0.00277777777770155933842"),
+    _pdec("0.0000661375632143793436117"),
+    _pdec("-0.00000165339022054652515390"),
+    _pdec("0.0000000413813679705723846039"),
+]
+_LG = [
+    _pdec("0.6666666666666735130"),
+    _pdec("0.3999999999940941908"),
+    _pdec("0.2857142874366239149"),
+    _pdec("0.2222219843214978396"),
+    _pdec("0.1818357216161805012"),
+    _pdec("0.1531383769920937332"),
+    _pdec("0.1479819860511658591"),
+]
+
+
+def _check(value: int, bits: int = 256) -> int:
+    if not -(2 ** (bits - 1)) <= value < 2 ** (bits - 1):
+        raise _Overflow()
+    return value
+
+
+def _truncating_div(a: int, b: int) -> int:
+    if b == 0:
+        raise _Overflow()
+    quotient = abs(a) // abs(b)
+    return quotient if (a < 0) == (b < 0) else -quotient
+
+
+def _add(a: int, b: int) -> int:
+    return _check(a + b)
+
+
+def _sub(a: int, b: int) -> int:
+    return _check(a - b)
+
+
+def _mul(a: int, b: int) -> int:
+    return _check(_truncating_div(_check(a * b, 384), _ONE))
+
+
+def _div(a: int, b: int) -> int:
+    return _check(_truncating_div(_check(a * _ONE, 384), b))
+
+
+def _horner(x: int, coefficients: list) -> int:
+    value = 0
+    for coefficient in reversed(coefficients):
+        value = _add(coefficient, _mul(x, value))
+    return value
+
+
+def _exp(x: int) -> int:
+    """
+    Mirror of `ExponentialPreciseDecimal::exp`.
+    """
+    if x == 0:
+        return _ONE
+    if x < -82 * _ONE:
+        return 0
+    if x > 93 * _ONE:
+        raise _Overflow()
+
+    # (1) Argument Reduction
+    signed_half = -_HALF if x < 0 else _HALF
+    k = _truncating_div(_add(_mul(_INVLN2, x), signed_half), _ONE)
+    r = _sub(x, _mul(_LN2, k * _ONE))
+
+    # (2) Approximation of exp(r)
+    rr = _mul(r, r)
+    c = _sub(r, _mul(rr, _horner(rr, _P)))
+    exp_r = _add(_add(_ONE, r), _div(_mul(r, c), _sub(_TWO, c)))
+
+    # (3) Scale back
+    two_pow_k = _ONE >> abs(k) if x < 0 else _ONE << k
+    return _mul(two_pow_k, exp_r)
+
+
+def _leading_zeros(value: int) -> int:
+    return 256 - value.bit_length()
+
+
+def _log_reduce_argument(number: int) -> tuple:
+    """
+    Mirror of `log_reduce_argument` finding k and f such that x = 2^k * (1+f)
+    with sqrt(2)/2 < 1+f < sqrt(2).
+    """
+    full_integer = _truncating_div(number, _ONE)
+    if full_integer == 0:
+        if number >= _SQRT_HALF:
+            return 0, number
+        k = _leading_zeros(number) - _leading_zeros(_SQRT_HALF)
+        r = _mul(number, _check(_ONE << k))
+        if r >= _SQRT_HALF:
+            return -k, r
+        return -k - 1, _mul(r, _TWO)
+
+    k = 255 - _leading_zeros(full_integer)
+    r = _div(number, _check(_ONE << k))
+    if r <= _SQRT:
+        return k, r
+    return k + 1, _div(r, _TWO)
+
+
+def _ln(x: int) -> int:
+    """
+    Mirror of `LogarithmPreciseDecimal::ln`.
+    """
+    if x <= 0:
+        raise _Overflow()
+
+    # (1) Argument Reduction
+    k, r = _log_reduce_argument(x)
+
+    # (2) Approximation of log(1+f)
+    f = _sub(r, _ONE)
+    s = _div(f, _add(_TWO, f))
+    z = _mul(s, s)
+    w = _mul(z, z)
+    t1 = _mul(z, _horner(w, _LG[0::2]))
+    t2 = _mul(w, _horner(w, _LG[1::2]))
+    log_1_f = _sub(f, _mul(s, _sub(f, _add(t1, t2))))
+
+    # (3) log(x) = k*ln2 + log(1+f)
+    return _add(_mul(_LN2, k * _ONE), log_1_f)
+
+
+def _log2(x: int) -> int:
+    """
+    Mirror of `LogarithmPreciseDecimal::log2`.
+    """
+    return _div(_ln(x), _LN2)
+
+
+def _log10(x: int) -> int:
+    """
+    Mirror of `LogarithmPreciseDecimal::log10`.
+    """
+    return _div(_ln(x), _LN10)
+
+
+def _log_base(x: int, base: int) -> int:
+    """
+    Mirror of `LogarithmPreciseDecimal::log_base`.
+    """
+    base_ln = _ln(base)
+    return _div(_ln(x), base_ln)
+
+
+def _pow(base: int, exp: int) -> int:
+    """
+    Mirror of `PowerPreciseDecimal::pow`.
+    """
+    if exp == 0 or base == _ONE:
+        return _ONE
+    if base == 0:
+        if exp > 0:
+            return 0
+        raise _Overflow()
+    if exp == _ONE:
+        return base
+    if exp == -_ONE:
+        return _div(_ONE, base)
+
+    if base < 0:
+        exp_integer = _truncating_div(exp, _ONE)
+        if exp_integer * _ONE != exp or not -(2**31) <= exp_integer < 2**31:
+            raise _Overflow()
+        magnitude = _exp(_mul(_ln(_check(-base)), exp))
+        return magnitude if exp_integer % 2 == 0 else _check(-magnitude)
+
+    return _exp(_mul(_ln(base), exp))
+
+
 def relative_error(result: decimal.Decimal, error: decimal.Decimal):
     with localcontext() as context:
         context.prec = 40
@@ -213,4 +495,363 @@ def error_pow(base: decimal.Decimal, exp: decimal.Decimal) -> decimal.Decimal:
     with localcontext() as context:
         context.prec = 40
         e_exp = base.ln() * exp
-        return error_exp(e_exp) + e_exp.exp() * error_ln() * exp
+        return error_exp(e_exp) + e_exp.exp() * error_ln() * abs(exp)
+
+
+def error_log2() -> decimal.Decimal:
+    """
+    `log2` divides `ln` by the constant `ln(2)`, scaling the error of `ln` alike:
+
+    ```txt
+    error_log2 = error_ln / ln(2) ~ 3.7*10^-18
+    ```
+    """
+    with localcontext() as context:
+        context.prec = 40
+        return error_ln() / decimal.Decimal(2).ln()
+
+
+def error_log10() -> decimal.Decimal:
+    """
+    `log10` divides `ln` by the constant `ln(10)`, scaling the error of `ln` alike:
+
+    ```txt
+    error_log10 = error_ln / ln(10) ~ 1.1*10^-18
+    ```
+    """
+    with localcontext() as context:
+        context.prec = 40
+        return error_ln() / decimal.Decimal(10).ln()
+
+
+def error_log_base(value: decimal.Decimal, base: decimal.Decimal) -> decimal.Decimal:
+    """
+    `log_base` divides two approximations of `ln`, each with its own error:
+    ```txt
+    ln'(x) / ln'(b) = (ln(x) + error_x) / (ln(b) + error_b)
+    ```
+
+    The difference to the exact result is:
+    ```txt
+    (ln(x) + error_x) / (ln(b) + error_b) - ln(x) / ln(b)
+    = (error_x * ln(b) - error_b * ln(x)) / (ln(b) * (ln(b) + error_b))
+    ```
+
+    Bounding `|error_x|` and `|error_b|` by `error_ln` results in:
+    ```txt
+    error_log_base(x, b) = error_ln * (1 + |log_b(x)|) / (|ln(b)| - error_ln)
+    ```
+    The error grows without bound for bases close to 1, where `ln(b)` cannot be
+    separated from its own error.
+    """
+    with localcontext() as context:
+        context.prec = 40
+        ln_base = abs(base.ln())
+        if ln_base <= error_ln():
+            raise ValueError(f"ln({base}) cannot be separated from its error.")
+        log_base = abs(value.ln() / ln_base)
+        return error_ln() * (1 + log_base) / (ln_base - error_ln())
+
+
+def error_narrowing() -> decimal.Decimal:
+    """
+    `Decimal` results are calculated with `PreciseDecimal` and narrowed to 18 decimal
+    places truncating towards zero, adding an error of less than one unit in the last
+    place on top of the error of the `PreciseDecimal` calculation:
+
+    ```txt
+    error_narrowing = 10^-18
+    ```
+    """
+    return decimal.Decimal(10) ** -Decimal.decimal_places
+
+
+TEST_VECTOR_VERSION = 3
+TEST_VECTOR_SEED = 0x5C297B0A
+TEST_VECTOR_SAMPLES = 20
+
+# truncation of intermediate results in units of 10^-36, scaled with 2^k for exp and pow
+_TRUNCATION_ULPS = 4
+_LIMITS = {
+    "Decimal": {
+        "max": decimal.Decimal(2**191 - 1) / 10**18,
+        "exp_min": -42,
+        "exp_max": 90,
+    },
+    "PreciseDecimal": {
+        "max": decimal.Decimal(2**255 - 1) / 10**36,
+        "exp_min": -82,
+        "exp_max": 93,
+    },
+}
+
+
+def _ulp(decimal_type: str) -> decimal.Decimal:
+    if decimal_type == "Decimal":
+        return decimal.Decimal(10) ** -Decimal.decimal_places
+    return decimal.Decimal(10) ** -PreciseDecimal.decimal_places
+
+
+def _exp_k(value: decimal.Decimal) -> int:
+    with localcontext() as context:
+        context.prec = 40
+        signed_half = (
+            decimal.Decimal("-0.5")
+            if value < decimal.Decimal(0)
+            else decimal.Decimal("0.5")
+        )
+        return int(value / decimal.Decimal(2).ln() + signed_half)
+
+
+def _truncation_error(k: int = 0) -> decimal.Decimal:
+    with localcontext() as context:
+        context.prec = 40
+        ulps = _TRUNCATION_ULPS * (decimal.Decimal(2) ** k + 1)
+        return ulps * _ulp("PreciseDecimal")
+
+
+def _reference(function: str, args: list) -> Optional[decimal.Decimal]:
+    """
+    Mathematically exact result with 100 significant digits, None outside of the domain.
+    """
+    with localcontext() as context:
+        context.prec = 100
+        if function == "exp":
+            return args[0].exp()
+        if function == "ln":
+            return args[0].ln() if args[0] > 0 else None
+        if function == "log2":
+            return args[0].ln() / decimal.Decimal(2).ln() if args[0] > 0 else None
+        if function == "log10":
+            return args[0].log10() if args[0] > 0 else None
+        if function == "log_base":
+            value, base = args
+            if value <= 0 or base <= 0 or base == 1:
+                return None
+            return value.ln() / base.ln()
+        if function == "pow":
+            base, exp = args
+            if exp == 0 or base == 1:
+                return decimal.Decimal(1)
+            if base == 0:
+                return decimal.Decimal(0) if exp > 0 else None
+            if base < 0 and exp != exp.to_integral_value():
+                return None
+            magnitude = (abs(base).ln() * exp).exp()
+            return -magnitude if base < 0 and int(exp) % 2 == 1 else magnitude
+        raise ValueError(f"Unknown function {function}.")
+
+
+def _max_error(function: str, args: list, decimal_type: str) -> decimal.Decimal:
+    """
+    Documented maximum error plus the truncation of intermediate results and of the
+    narrowing to `Decimal`.
+    """
+    with localcontext() as context:
+        context.prec = 40
+        if function == "exp":
+            value = args[0]
+            error = error_exp(value) + _truncation_error(_exp_k(value))
+            if value < _LIMITS[decimal_type]["exp_min"]:
+                # exp underflows to zero, missing the whole exact result
+                error += value.exp()
+        elif function == "ln":
+            error = error_ln() + _truncation_error()
+        elif function == "log2":
+            error = error_log2() + 2 * _truncation_error()
+        elif function == "log10":
+            error = error_log10() + 2 * _truncation_error()
+        elif function == "log_base":
+            value, base = args
+            # truncation of both logarithms is scaled like their approximation error
+            scale = error_log_base(value, base) / error_ln()
+            error = error_log_base(value, base) + _truncation_error() * (scale + 1)
+        elif function == "pow":
+            base, exp = args
+            if base == 0 or base == 1 or exp == 0:
+                error = decimal.Decimal(0)
+            else:
+                k = _exp_k(abs(base).ln() * exp)
+                error = error_pow(abs(base), exp) + _truncation_error(k)
+        else:
+            raise ValueError(f"Unknown function {function}.")
+        if decimal_type == "Decimal":
+            error += error_narrowing()
+        return error
+
+
+def _format(value: decimal.Decimal, rounding: str) -> str:
+    context = decimal.Context(prec=200)
+    text = format(value.quantize(_ulp("PreciseDecimal"), rounding, context), "f")
+    if "." in text:
+        text = text.rstrip("0").rstrip(".")
+    return "0" if text == "-0" else text
+
+
+def generate_test_vector(
+    function: str, decimal_type: str, *args: Union[str, decimal.Decimal]
+) -> dict:
+    """
+    Creates a single test vector for `function` evaluated with `decimal_type` on `args`.
+
+    ```txt
+    |result - expected| <= max_error        if expected is not None
+    result is None                          if expected is None
+    ```
+    Expected results are rounded to 36 decimal places, maximum errors rounded up.
+    `result` is the bit exact output of the Scrypto implementation.
+    """
+    args = [decimal.Decimal(arg) for arg in args]
+    limits = _LIMITS[decimal_type]
+    expected = _reference(function, args)
+    if function == "exp" and args[0] > limits["exp_max"]:
+        expected = None
+    if expected is not None and abs(expected) > limits["max"]:
+        expected = None
+    vector = {
+        "function": function,
+        "type": decimal_type,
+        "input": [_format(arg, decimal.ROUND_HALF_EVEN) for arg in args],
+        "expected": None,
+        "max_error": None,
+        "result": None,
+    }
+    if expected is not None:
+        max_error = _max_error(function, args, decimal_type)
+        vector["expected"] = _format(expected, decimal.ROUND_HALF_EVEN)
+        vector["max_error"] = _format(max_error, decimal.ROUND_CEILING)
+    scrypto_type = Decimal if decimal_type == "Decimal" else PreciseDecimal
+    result = getattr(scrypto_type(args[0]), f"scrypto_{function}")(*args[1:])
+    if result is not None:
+        vector["result"] = _format(result, decimal.ROUND_HALF_EVEN)
+    return vector
+
+
+def _uniform(
+    rng: random.Random, lower: int, upper: int, decimal_type: str
+) -> decimal.Decimal:
+    ulp = _ulp(decimal_type)
+    steps = int((upper - lower) / ulp)
+    return lower + rng.randrange(steps) * ulp
+
+
+def _log_uniform(
+    rng: random.Random, min_exponent: int, max_exponent: int, decimal_type: str
+) -> decimal.Decimal:
+    mantissa = decimal.Decimal(rng.randrange(10**17, 10**18)) / 10**17
+    exponent = rng.randrange(min_exponent, max_exponent)
+    value = mantissa * decimal.Decimal(10) ** exponent
+    context = decimal.Context(prec=200)
+    return value.quantize(_ulp(decimal_type), decimal.ROUND_FLOOR, context)
+
+
+def generate_test_vectors(
+    seed: int = TEST_VECTOR_SEED, samples: int = TEST_VECTOR_SAMPLES
+) -> dict:
+    """
+    Creates the shared test vectors of `exp`, `ln`, `log2`, `log10`, `log_base` and
+    `pow` for `Decimal` and
+    `PreciseDecimal`, consisting of special cases and `samples` random arguments per
+    function and type.
+    """
+    rng = random.Random(seed)
+    vectors = []
+    for decimal_type in ("Decimal", "PreciseDecimal"):
+        limits = _LIMITS[decimal_type]
+        ulp = _ulp(decimal_type)
+        exp_min, exp_max = limits["exp_min"], limits["exp_max"]
+        min_exponent = -18 if decimal_type == "Decimal" else -36
+        maximum = limits["max"].quantize(ulp, decimal.ROUND_FLOOR)
+
+        cases = ["0", ulp, -ulp, "0.5", "-0.5", "1", "-1", "10", "-10"]
+        cases += [exp_min, exp_min - 1, exp_max, exp_max + 1]
+        cases += [_uniform(rng, exp_min, exp_max, decimal_type) for _ in range(samples)]
+        vectors += [generate_test_vector("exp", decimal_type, x) for x in cases]
+
+        cases = ["-1", "0", ulp, "0.5", "1", "2", "10", "1000000", maximum]
+        cases += [
+            _log_uniform(rng, min_exponent, 39, decimal_type) for _ in range(samples)
+        ]
+        vectors += [generate_test_vector("ln", decimal_type, x) for x in cases]
+
+        cases = ["-1", "0", ulp, "0.5", "1", "2", "10", "1024", maximum]
+        cases += [
+            _log_uniform(rng, min_exponent, 39, decimal_type) for _ in range(samples)
+        ]
+        vectors += [generate_test_vector("log2", decimal_type, x) for x in cases]
+        vectors += [generate_test_vector("log10", decimal_type, x) for x in cases]
+
+        cases = [
+            ("8", "2"),
+            ("2", "8"),
+            ("1", "2"),
+            ("100", "10"),
+            ("0.5", "0.25"),
+            ("2", "1"),
+            ("0", "2"),
+            ("2", "0"),
+            ("-2", "2"),
+            ("2", "-2"),
+        ]
+        cases += [
+            (
+                _log_uniform(rng, min_exponent, 39, decimal_type),
+                _log_uniform(rng, -3, 3, decimal_type),
+            )
+            for _ in range(samples)
+        ]
+        vectors += [
+            generate_test_vector("log_base", decimal_type, x, b) for x, b in cases
+        ]
+
+        cases = [
+            ("0", "2"),
+            ("0", "-2"),
+            ("1", "1000"),
+            ("2", "0"),
+            ("2", "1"),
+            ("2", "-1"),
+            ("4", "0.5"),
+            ("2", "10"),
+            ("2", "-10"),
+            ("10", "41"),
+            ("-2", "3"),
+            ("-2", "-2"),
+            ("-2", "0.5"),
+            ("-5", "5"),
+        ]
+        cases += [
+            (
+                _log_uniform(rng, -3, 3, decimal_type),
+                _uniform(rng, -10, 10, decimal_type),
+            )
+            for _ in range(samples)
+        ]
+        cases += [
+            (
+                -_log_uniform(rng, -1, 1, decimal_type),
+                decimal.Decimal(rng.randrange(-15, 16)),
+            )
+            for _ in range(samples)
+        ]
+        vectors += [generate_test_vector("pow", decimal_type, x, y) for x, y in cases]
+    return {"version": TEST_VECTOR_VERSION, "vectors": vectors}
+
+
+def write_test_vectors(
+    path: str, seed: int = TEST_VECTOR_SEED, samples: int = TEST_VECTOR_SAMPLES
+):
+    """
+    Writes the test vectors to `path` as JSON with one vector per line.
+    """
+    data = generate_test_vectors(seed, samples)
+    lines = [json.dumps(vector) for vector in data["vectors"]]
+    with open(path, "w") as file:
+        file.write(f'{{\n  "version": {data["version"]},\n  "vectors": [\n    ')
+        file.write(",\n    ".join(lines))
+        file.write("\n  ]\n}\n")
+
+
+if __name__ == "__main__":
+    # python python/scryptomath.py tests/vectors/vectors.json
+    write_test_vectors(sys.argv[1])
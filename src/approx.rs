@@ -0,0 +1,269 @@
+// Approximate comparison of Decimal and PreciseDecimal
+//
+// Method:
+//      1. The ULP distance counts the representable values between a and b, which is the difference of
+//         their subunits (10^-18 for Decimal, 10^-36 for PreciseDecimal):
+//              ulp_distance(a, b) = |subunits(a) - subunits(b)|
+//
+//      2. The relative error of a result a with respect to a reference r:
+//              relative_error(a, r) = |a - r| / |r|
+//
+//      3. approx_eq(a, b, n) holds if ulp_distance(a, b) <= n, approx_eq_abs(a, b, e) holds if
+//              |a - b| <= e
+//         and approx_eq_rel(a, b, e) holds if
+//              |a - b| <= e * max(|a|, |b|)
+//         which unlike relative_error is symmetric in a and b and defined for zero. approx_eq_rel compares
+//         the subunits in I512, where neither side can overflow.
+//
+// Special cases:
+//      1. ulp_distance is None if the difference of the subunits overflows, e.g. of MIN and MAX.
+//      2. relative_error is None for a zero reference, unless the result is zero as well.
+//      3. approx_eq and approx_eq_abs are false if the difference overflows, as it exceeds every
+//         representable tolerance. Equal values are always approximately equal.
+//
+// Accuracy:
+//      ulp_distance, approx_eq, approx_eq_abs and approx_eq_rel are exact. relative_error is truncated
+//      like any other division. Decimal is compared via PreciseDecimal, the relative error of Decimal is
+//      narrowed back towards zero.
+
+use radix_common::math::{
+    CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, Decimal, PreciseDecimal, I192, I256, I512,
+};
+
+pub trait ApproxDecimal {
+    fn ulp_distance(&self, other: Decimal) -> Option<I192>;
+    fn relative_error(&self, reference: Decimal) -> Option<Decimal>;
+    fn approx_eq(&self, other: Decimal, max_ulps: u64) -> bool;
+    fn approx_eq_abs(&self, other: Decimal, max_error: Decimal) -> bool;
+    fn approx_eq_rel(&self, other: Decimal, max_relative_error: Decimal) -> bool;
+}
+
+pub trait ApproxPreciseDecimal {
+    fn ulp_distance(&self, other: PreciseDecimal) -> Option<I256>;
+    fn relative_error(&self, reference: PreciseDecimal) -> Option<PreciseDecimal>;
+    fn approx_eq(&self, other: PreciseDecimal, max_ulps: u64) -> bool;
+    fn approx_eq_abs(&self, other: PreciseDecimal, max_error: PreciseDecimal) -> bool;
+    fn approx_eq_rel(&self, other: PreciseDecimal, max_relative_error: PreciseDecimal) -> bool;
+}
+
+impl ApproxDecimal for Decimal {
+    /// Calculates the number of attos (10^-18) between two Decimals
+    fn ulp_distance(&self, other: Decimal) -> Option<I192> {
+        let (lower, upper) = if *self < other {
+            (*self, other)
+        } else {
+            (other, *self)
+        };
+        upper.attos().checked_sub(lower.attos())
+    }
+
+    /// Calculates the relative error `|self - reference| / |reference|` of a Decimal
+    /// Using the relative error of PreciseDecimal internally
+    fn relative_error(&self, reference: Decimal) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .relative_error(reference.into())
+            .and_then(|error| error.try_into().ok())
+    }
+
+    /// Checks whether two Decimals are at most `max_ulps` attos apart
+    fn approx_eq(&self, other: Decimal, max_ulps: u64) -> bool {
+        self.ulp_distance(other)
+            .is_some_and(|distance| distance <= I192::from(max_ulps))
+    }
+
+    /// Checks whether two Decimals differ by at most `max_error`
+    fn approx_eq_abs(&self, other: Decimal, max_error: Decimal) -> bool {
+        self.ulp_distance(other)
+            .is_some_and(|distance| distance <= max_error.attos())
+    }
+
+    /// Checks whether two Decimals differ by at most `max_relative_error` relative to the larger magnitude
+    /// Using the approximate comparison of PreciseDecimal internally
+    fn approx_eq_rel(&self, other: Decimal, max_relative_error: Decimal) -> bool {
+        PreciseDecimal::from(*self).approx_eq_rel(other.into(), max_relative_error.into())
+    }
+}
+
+impl ApproxPreciseDecimal for PreciseDecimal {
+    /// Calculates the number of subunits (10^-36) between two PreciseDecimals
+    fn ulp_distance(&self, other: PreciseDecimal) -> Option<I256> {
+        let (lower, upper) = if *self < other {
+            (*self, other)
+        } else {
+            (other, *self)
+        };
+        upper
+            .precise_subunits()
+            .checked_sub(lower.precise_subunits())
+    }
+
+    /// Calculates the relative error `|self - reference| / |reference|` of a PreciseDecimal
+    fn relative_error(&self, reference: PreciseDecimal) -> Option<PreciseDecimal> {
+        if *self == reference {
+            return Some(PreciseDecimal::ZERO);
+        }
+        self.checked_sub(reference)?
+            .checked_abs()?
+            .checked_div(reference.checked_abs()?)
+    }
+
+    /// Checks whether two PreciseDecimals are at most `max_ulps` subunits apart
+    fn approx_eq(&self, other: PreciseDecimal, max_ulps: u64) -> bool {
+        self.ulp_distance(other)
+            .is_some_and(|distance| distance <= I256::from(max_ulps))
+    }
+
+    /// Checks whether two PreciseDecimals differ by at most `max_error`
+    fn approx_eq_abs(&self, other: PreciseDecimal, max_error: PreciseDecimal) -> bool {
+        self.ulp_distance(other)
+            .is_some_and(|distance| distance <= max_error.precise_subunits())
+    }
+
+    /// Checks whether two PreciseDecimals differ by at most `max_relative_error` relative to the larger magnitude
+    fn approx_eq_rel(&self, other: PreciseDecimal, max_relative_error: PreciseDecimal) -> bool {
+        if *self == other {
+            return true;
+        }
+        within_relative_error(
+            I512::from(self.precise_subunits()),
+            I512::from(other.precise_subunits()),
+            I512::from(max_relative_error.precise_subunits()),
+        )
+        .unwrap_or(false)
+    }
+}
+
+/// Checks `|a - b| * 10^36 <= max(|a|, |b|) * e` on subunits widened to I512
+fn within_relative_error(a: I512, b: I512, max_relative_error: I512) -> Option<bool> {
+    let one = I512::from(PreciseDecimal::ONE.precise_subunits());
+    let difference = magnitude(a.checked_sub(b)?)?.checked_mul(one)?;
+    let bound = magnitude(a)?
+        .max(magnitude(b)?)
+        .checked_mul(max_relative_error)?;
+    Some(difference <= bound)
+}
+
+fn magnitude(value: I512) -> Option<I512> {
+    if value.is_negative() {
+        return value.checked_neg();
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common_derive::{dec, pdec};
+    use test_case::test_case;
+
+    #[test_case(dec!(1), dec!(1), Some(0); "equal")]
+    #[test_case(dec!(1), dec!("1.000000000000000003"), Some(3); "above")]
+    #[test_case(dec!("1.000000000000000003"), dec!(1), Some(3); "below")]
+    #[test_case(dec!("-0.000000000000000001"), dec!("0.000000000000000001"), Some(2); "across zero")]
+    #[test_case(Decimal::MIN, Decimal::MAX, None; "overflow")]
+    fn test_ulp_distance_dec(a: Decimal, b: Decimal, expected: Option<i64>) {
+        assert_eq!(a.ulp_distance(b), expected.map(I192::from));
+    }
+
+    #[test_case(pdec!(1), pdec!(1), Some(0); "equal")]
+    #[test_case(pdec!(1), pdec!("1.000000000000000000000000000000000003"), Some(3); "above")]
+    #[test_case(pdec!("1.000000000000000000000000000000000003"), pdec!(1), Some(3); "below")]
+    #[test_case(pdec!(1), pdec!("1.000000000000000001"), Some(1_000_000_000_000_000_000); "decimal ulp")]
+    #[test_case(PreciseDecimal::MIN, PreciseDecimal::MAX, None; "overflow")]
+    fn test_ulp_distance_pdec(a: PreciseDecimal, b: PreciseDecimal, expected: Option<i64>) {
+        assert_eq!(a.ulp_distance(b), expected.map(I256::from));
+    }
+
+    #[test_case(dec!(101), dec!(100), Some(dec!("0.01")); "positive")]
+    #[test_case(dec!(-99), dec!(-100), Some(dec!("0.01")); "negative")]
+    #[test_case(dec!(0), dec!(0), Some(dec!(0)); "zero")]
+    #[test_case(dec!(1), dec!(0), None; "zero reference")]
+    #[test_case(dec!(1), dec!(3), Some(dec!("0.666666666666666666")); "truncated")]
+    fn test_relative_error_dec(result: Decimal, reference: Decimal, expected: Option<Decimal>) {
+        assert_eq!(result.relative_error(reference), expected);
+    }
+
+    #[test_case(pdec!(101), pdec!(100), Some(pdec!("0.01")); "positive")]
+    #[test_case(pdec!(-99), pdec!(-100), Some(pdec!("0.01")); "negative")]
+    #[test_case(pdec!(0), pdec!(0), Some(pdec!(0)); "zero")]
+    #[test_case(pdec!(1), pdec!(0), None; "zero reference")]
+    #[test_case(PreciseDecimal::MIN, PreciseDecimal::MAX, None; "overflow")]
+    fn test_relative_error_pdec(
+        result: PreciseDecimal,
+        reference: PreciseDecimal,
+        expected: Option<PreciseDecimal>,
+    ) {
+        assert_eq!(result.relative_error(reference), expected);
+    }
+
+    #[test_case(dec!(1), dec!("1.000000000000000002"), 2, true; "within")]
+    #[test_case(dec!(1), dec!("1.000000000000000003"), 2, false; "outside")]
+    #[test_case(dec!(1), dec!(1), 0, true; "equal")]
+    #[test_case(Decimal::MIN, Decimal::MAX, u64::MAX, false; "overflow")]
+    fn test_approx_eq_dec(a: Decimal, b: Decimal, max_ulps: u64, expected: bool) {
+        assert_eq!(a.approx_eq(b, max_ulps), expected);
+        assert_eq!(b.approx_eq(a, max_ulps), expected);
+    }
+
+    #[test_case(pdec!(1), pdec!("1.000000000000000000000000000000000002"), 2, true; "within")]
+    #[test_case(pdec!(1), pdec!("1.000000000000000000000000000000000003"), 2, false; "outside")]
+    #[test_case(pdec!(-1), pdec!("-1.000000000000000000000000000000000002"), 2, true; "negative")]
+    #[test_case(PreciseDecimal::MIN, PreciseDecimal::MAX, u64::MAX, false; "overflow")]
+    fn test_approx_eq_pdec(a: PreciseDecimal, b: PreciseDecimal, max_ulps: u64, expected: bool) {
+        assert_eq!(a.approx_eq(b, max_ulps), expected);
+        assert_eq!(b.approx_eq(a, max_ulps), expected);
+    }
+
+    #[test_case(dec!(1), dec!("1.000000000000000002"), dec!("0.000000000000000002"), true; "within")]
+    #[test_case(dec!(1), dec!("1.000000000000000003"), dec!("0.000000000000000002"), false; "outside")]
+    #[test_case(dec!(-1), dec!(1), dec!(2), true; "across zero")]
+    #[test_case(Decimal::MIN, Decimal::MAX, Decimal::MAX, false; "overflow")]
+    fn test_approx_eq_abs_dec(a: Decimal, b: Decimal, max_error: Decimal, expected: bool) {
+        assert_eq!(a.approx_eq_abs(b, max_error), expected);
+        assert_eq!(b.approx_eq_abs(a, max_error), expected);
+    }
+
+    #[test_case(pdec!(1), pdec!("1.000000000000000001"), pdec!("0.000000000000000001"), true; "within")]
+    #[test_case(pdec!(1), pdec!("1.000000000000000001000000000000000001"), pdec!("0.000000000000000001"), false; "outside")]
+    #[test_case(pdec!(-1), pdec!("-1.000000000000000000000000000000000001"), pdec!("0.000000000000000000000000000000000001"), true; "negative")]
+    #[test_case(PreciseDecimal::MIN, PreciseDecimal::MAX, PreciseDecimal::MAX, false; "overflow")]
+    fn test_approx_eq_abs_pdec(
+        a: PreciseDecimal,
+        b: PreciseDecimal,
+        max_error: PreciseDecimal,
+        expected: bool,
+    ) {
+        assert_eq!(a.approx_eq_abs(b, max_error), expected);
+        assert_eq!(b.approx_eq_abs(a, max_error), expected);
+    }
+
+    #[test_case(dec!(100), dec!(101), dec!("0.01"), true; "within")]
+    #[test_case(dec!(100), dec!(102), dec!("0.01"), false; "outside")]
+    #[test_case(dec!(0), dec!(0), dec!(0), true; "zero")]
+    #[test_case(dec!(0), dec!("0.000000000000000001"), dec!("0.5"), false; "close to zero")]
+    #[test_case(Decimal::MIN, Decimal::MAX, dec!(2), true; "opposite signs")]
+    #[test_case(Decimal::MIN, Decimal::MIN + dec!("0.000000000000000001"), dec!("0.000000000000000001"), true; "min neighbour")]
+    fn test_approx_eq_rel_dec(a: Decimal, b: Decimal, max_relative_error: Decimal, expected: bool) {
+        assert_eq!(a.approx_eq_rel(b, max_relative_error), expected);
+        assert_eq!(b.approx_eq_rel(a, max_relative_error), expected);
+    }
+
+    #[test_case(pdec!(100), pdec!(101), pdec!("0.01"), true; "within")]
+    #[test_case(pdec!(100), pdec!(102), pdec!("0.01"), false; "outside")]
+    #[test_case(pdec!(-100), pdec!(-101), pdec!("0.01"), true; "negative")]
+    #[test_case(pdec!(0), pdec!("0.000000000000000000000000000000000001"), pdec!("0.5"), false; "close to zero")]
+    #[test_case(PreciseDecimal::MAX, PreciseDecimal::MAX - pdec!(1), PreciseDecimal::MAX, true; "large bound")]
+    #[test_case(PreciseDecimal::MIN, PreciseDecimal::MAX, pdec!(2), true; "opposite signs")]
+    #[test_case(PreciseDecimal::MIN, PreciseDecimal::MAX, pdec!("1.999999999999999999999999999999999999"), false; "opposite signs outside")]
+    #[test_case(PreciseDecimal::MIN, PreciseDecimal::MIN + pdec!("0.000000000000000000000000000000000001"), pdec!("0.000000000000000000000000000000000001"), true; "min neighbour")]
+    fn test_approx_eq_rel_pdec(
+        a: PreciseDecimal,
+        b: PreciseDecimal,
+        max_relative_error: PreciseDecimal,
+        expected: bool,
+    ) {
+        assert_eq!(a.approx_eq_rel(b, max_relative_error), expected);
+        assert_eq!(b.approx_eq_rel(a, max_relative_error), expected);
+    }
+}
@@ -0,0 +1,224 @@
+// Fixed point arithmetic with 100 decimals on big integers
+//
+// Shared by the Remez coefficient generator and the differential tests in tests/differential.rs,
+// which use it as arbitrary precision reference for exp and ln.
+//
+// Method:
+//      e^x   = 2^k * e^r with x = k*ln2 + r, |r| <= ln2/2, and a Taylor series for e^r
+//      ln(x) = k*ln2 + 2*atanh((y-1)/(y+1)) with x = 2^k * y, 1/2 <= y < 1
+//      ln2   = 2*atanh(1/3)
+//      The series are summed until their terms vanish at 100 decimals, all operations truncate.
+
+#![allow(dead_code)] // each user needs a different subset
+
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::sync::OnceLock;
+
+pub const DIGITS: u32 = 100;
+pub const OUTPUT_DIGITS: u32 = 36;
+
+/// Fixed point number with `DIGITS` decimals
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fixed(pub BigInt);
+
+pub fn scale() -> BigInt {
+    BigInt::from(10).pow(DIGITS)
+}
+
+impl Fixed {
+    pub fn zero() -> Self {
+        Fixed(BigInt::zero())
+    }
+
+    pub fn one() -> Self {
+        Fixed(scale())
+    }
+
+    pub fn from_int(value: i64) -> Self {
+        Fixed(BigInt::from(value) * scale())
+    }
+
+    /// Parses a decimal string like "-0.125"
+    pub fn parse(value: &str) -> Option<Self> {
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, value),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if fraction.len() > DIGITS as usize
+            || (integer.is_empty() && fraction.is_empty())
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let padded = format!("{integer}{fraction:0<width$}", width = DIGITS as usize);
+        let subunits: BigInt = padded.parse().ok()?;
+        Some(Fixed(if negative { -subunits } else { subunits }))
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0.is_negative()
+    }
+
+    pub fn abs(&self) -> Self {
+        Fixed(self.0.abs())
+    }
+
+    pub fn sqrt(&self) -> Self {
+        Fixed((&self.0 * scale()).sqrt())
+    }
+
+    pub fn powi(&self, exponent: usize) -> Self {
+        (0..exponent).fold(Fixed::one(), |power, _| &power * self)
+    }
+
+    /// Rounds half away from zero to `decimals` decimals
+    pub fn round(&self, decimals: u32) -> Self {
+        let unit = BigInt::from(10).pow(DIGITS - decimals);
+        let half = &unit / 2;
+        let rounded: BigInt = (self.0.abs() + half) / &unit * &unit;
+        Fixed(if self.is_negative() {
+            -rounded
+        } else {
+            rounded
+        })
+    }
+
+    /// Formats the value with `decimals` decimals, truncating the remaining digits
+    pub fn to_decimal_string(&self, decimals: u32) -> String {
+        let digits = self.0.abs() / BigInt::from(10).pow(DIGITS - decimals);
+        let unit = BigInt::from(10).pow(decimals);
+        let sign = if self.is_negative() && !digits.is_zero() {
+            "-"
+        } else {
+            ""
+        };
+        let fraction = format!(
+            "{:0>width$}",
+            (&digits % &unit).to_string(),
+            width = decimals as usize
+        );
+        format!("{sign}{}.{fraction}", &digits / &unit)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64().unwrap_or(f64::NAN) / 10f64.powi(DIGITS as i32)
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_decimal_string(OUTPUT_DIGITS))
+    }
+}
+
+impl Add for &Fixed {
+    type Output = Fixed;
+    fn add(self, other: &Fixed) -> Fixed {
+        Fixed(&self.0 + &other.0)
+    }
+}
+
+impl Sub for &Fixed {
+    type Output = Fixed;
+    fn sub(self, other: &Fixed) -> Fixed {
+        Fixed(&self.0 - &other.0)
+    }
+}
+
+impl Mul for &Fixed {
+    type Output = Fixed;
+    fn mul(self, other: &Fixed) -> Fixed {
+        Fixed(&self.0 * &other.0 / scale())
+    }
+}
+
+impl Div for &Fixed {
+    type Output = Fixed;
+    fn div(self, other: &Fixed) -> Fixed {
+        Fixed(&self.0 * scale() / &other.0)
+    }
+}
+
+impl Neg for &Fixed {
+    type Output = Fixed;
+    fn neg(self) -> Fixed {
+        Fixed(-&self.0)
+    }
+}
+
+/// Sums a series until the terms vanish at the working precision
+pub fn sum_series(mut term: impl FnMut(u32) -> Fixed) -> Fixed {
+    let mut sum = Fixed::zero();
+    for n in 0.. {
+        let t = term(n);
+        if t.0.is_zero() {
+            break;
+        }
+        sum = &sum + &t;
+    }
+    sum
+}
+
+/// atanh(t) = t + t^3/3 + t^5/5 + ... for |t| <= 1/3
+pub fn atanh(t: &Fixed) -> Fixed {
+    let tt = t * t;
+    let mut power = t.clone();
+    sum_series(|n| {
+        let term = Fixed(&power.0 / BigInt::from(2 * n + 1));
+        power = &power * &tt;
+        term
+    })
+}
+
+/// ln(2) = 2*atanh(1/3), calculated once
+pub fn ln2() -> Fixed {
+    static LN2: OnceLock<Fixed> = OnceLock::new();
+    LN2.get_or_init(|| {
+        let third = &Fixed::one() / &Fixed::from_int(3);
+        &atanh(&third) + &atanh(&third)
+    })
+    .clone()
+}
+
+pub fn exp(x: &Fixed) -> Fixed {
+    let ln2 = ln2();
+    let k = (x / &ln2).to_f64().round() as i64;
+    let r = x - &(&ln2 * &Fixed::from_int(k));
+    let mut term = Fixed::one();
+    let exp_r = &Fixed::one()
+        + &sum_series(|n| {
+            term = &(&term * &r) / &Fixed::from_int(n as i64 + 1);
+            term.clone()
+        });
+    let shift = k.unsigned_abs() as usize;
+    if k < 0 {
+        Fixed(exp_r.0 >> shift)
+    } else {
+        Fixed(exp_r.0 << shift)
+    }
+}
+
+pub fn ln(x: &Fixed) -> Fixed {
+    // x = 2^k * y with 1/2 <= y < 1
+    let mut k: i64 = 0;
+    let mut y = x.clone();
+    let half = &Fixed::one() / &Fixed::from_int(2);
+    while y >= Fixed::one() {
+        y = Fixed(y.0 >> 1);
+        k += 1;
+    }
+    while y < half {
+        y = Fixed(y.0 << 1);
+        k -= 1;
+    }
+    let t = &(&y - &Fixed::one()) / &(&y + &Fixed::one());
+    let ln_y = &atanh(&t) + &atanh(&t);
+    &(&ln2() * &Fixed::from_int(k)) + &ln_y
+}
@@ -0,0 +1,531 @@
+// Remez exchange algorithm for minimax polynomial coefficients
+//
+// Usage:
+//      cargo run --release --features remez --bin remez -- <function> <lower> <upper> <degree>
+//          [--min-power <k>] [--relative] [--name <prefix>] [--iterations <n>]
+//
+// Functions:
+//      exp     e^x
+//      ln      ln(x), requires lower > 0
+//      ln1p    ln(1+x), requires lower > -1
+//      sqrt    sqrt(x), requires lower >= 0
+//      exp-r   R(z) - 2 with R(r^2) = r*(e^r+1)/(e^r-1), target of P1..P5 in exponential.rs
+//              on [0, (ln2/2)^2] = [0, 0.120112240878645] with --min-power 1
+//      log-r   R(z) with ln(1+f) = 2s + s*R(s^2), s = f/(2+f), target of LG1..LG7 in logarithm.rs
+//              on [0, (3-2*sqrt(2))^2] = [0, 0.029437251522859] with --min-power 1
+//
+// Method:
+//      1. All arithmetic is done in fixed point with 100 decimals on big integers, see fixed.rs.
+//
+//      2. The polynomial p(x) = Σ c_k x^k, k = min_power..degree, with m coefficients is fitted to
+//         f(x) on m+1 reference points x_i starting from Chebyshev nodes by solving
+//              p(x_i) + (-1)^i * E * w(x_i) = f(x_i)
+//         with w = 1 for the absolute and w = |f| for the relative error.
+//
+//      3. The local extrema of the weighted error (f - p) / w between its sign changes are located
+//         on a grid, refined by golden section search and become the new reference points.
+//
+//      4. The iteration stops once the extrema are levelled, i.e. max|e| / min|e| - 1 < 10^-6.
+//
+// Output:
+//      The coefficients rounded to 36 decimals as pdec! constants together with the maximum error of
+//      the rounded coefficients on a dense grid, in absolute terms and as power of two.
+
+mod fixed;
+
+use fixed::{exp, ln, sum_series, Fixed, OUTPUT_DIGITS};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use std::env;
+use std::process::ExitCode;
+
+const GRID_POINTS_PER_COEFFICIENT: usize = 64;
+const VERIFY_POINTS: usize = 20000;
+const GOLDEN_ITERATIONS: usize = 80;
+const DEFAULT_ITERATIONS: usize = 30;
+const LEVELLED: &str = "0.000001";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Function {
+    Exp,
+    Ln,
+    Ln1p,
+    Sqrt,
+    ExpR,
+    LogR,
+}
+
+impl Function {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "exp" => Some(Function::Exp),
+            "ln" => Some(Function::Ln),
+            "ln1p" => Some(Function::Ln1p),
+            "sqrt" => Some(Function::Sqrt),
+            "exp-r" => Some(Function::ExpR),
+            "log-r" => Some(Function::LogR),
+            _ => None,
+        }
+    }
+
+    /// Checks that the function is defined on [lower, upper]
+    fn is_defined(&self, lower: &Fixed) -> bool {
+        match self {
+            Function::Ln => !lower.is_negative() && !lower.0.is_zero(),
+            Function::Ln1p => lower > &Fixed::from_int(-1),
+            Function::Sqrt | Function::ExpR | Function::LogR => !lower.is_negative(),
+            Function::Exp => true,
+        }
+    }
+
+    fn evaluate(&self, x: &Fixed) -> Fixed {
+        match self {
+            Function::Exp => exp(x),
+            Function::Ln => ln(x),
+            Function::Ln1p => ln(&(&Fixed::one() + x)),
+            Function::Sqrt => x.sqrt(),
+            Function::ExpR => {
+                // r*(e^r+1)/(e^r-1) - 2 = z/6 - z^2/360 + z^3/15120 - ...
+                let r = x.sqrt();
+                if r.0.is_zero() {
+                    return Fixed::zero();
+                }
+                let e = exp(&r);
+                let ratio = &(&e + &Fixed::one()) / &(&e - &Fixed::one());
+                &(&r * &ratio) - &Fixed::from_int(2)
+            }
+            Function::LogR => {
+                // R(z) = Σ_k>=1 2*z^k/(2k+1)
+                let mut power = x.clone();
+                sum_series(|n| {
+                    let term = Fixed(&power.0 * 2 / BigInt::from(2 * n + 3));
+                    power = &power * x;
+                    term
+                })
+            }
+        }
+    }
+}
+
+struct Remez {
+    function: Function,
+    lower: Fixed,
+    upper: Fixed,
+    powers: Vec<usize>,
+    relative: bool,
+}
+
+impl Remez {
+    fn polynomial(&self, coefficients: &[Fixed], x: &Fixed) -> Fixed {
+        self.powers
+            .iter()
+            .zip(coefficients)
+            .fold(Fixed::zero(), |sum, (power, c)| {
+                &sum + &(c * &x.powi(*power))
+            })
+    }
+
+    fn weight(&self, f: &Fixed) -> Fixed {
+        if self.relative {
+            f.abs()
+        } else {
+            Fixed::one()
+        }
+    }
+
+    /// Weighted error (f - p) / w at x
+    fn error(&self, coefficients: &[Fixed], x: &Fixed) -> Fixed {
+        let f = self.function.evaluate(x);
+        let weight = self.weight(&f);
+        if weight.0.is_zero() {
+            return Fixed::zero();
+        }
+        &(&f - &self.polynomial(coefficients, x)) / &weight
+    }
+
+    /// Point `i` of `n` equidistant intervals on [lower, upper]
+    fn grid(&self, i: usize, n: usize) -> Fixed {
+        let width = &self.upper - &self.lower;
+        &self.lower + &Fixed(width.0 * BigInt::from(i) / BigInt::from(n))
+    }
+
+    /// Chebyshev nodes of the first kind as initial reference, which exclude the interval ends where
+    /// a basis without constant term vanishes
+    fn chebyshev(&self, n: usize) -> Vec<Fixed> {
+        let half_width = &(&self.upper - &self.lower) / &Fixed::from_int(2);
+        let mid = &self.lower + &half_width;
+        (0..n)
+            .map(|i| {
+                let angle = std::f64::consts::PI * (2 * (n - i) - 1) as f64 / (2 * n) as f64;
+                let cos = Fixed::parse(&format!("{:.15}", angle.cos())).unwrap_or(Fixed::zero());
+                &mid + &(&half_width * &cos)
+            })
+            .collect()
+    }
+
+    /// Solves p(x_i) + (-1)^i * E * w(x_i) = f(x_i) for the coefficients and E
+    fn solve(&self, reference: &[Fixed]) -> Option<(Vec<Fixed>, Fixed)> {
+        let n = reference.len();
+        let mut rows: Vec<Vec<Fixed>> = reference
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let f = self.function.evaluate(x);
+                let sign = if i % 2 == 0 { 1 } else { -1 };
+                let mut row: Vec<Fixed> = self.powers.iter().map(|p| x.powi(*p)).collect();
+                row.push(&self.weight(&f) * &Fixed::from_int(sign));
+                row.push(f);
+                row
+            })
+            .collect();
+
+        // Gaussian elimination with partial pivoting
+        for column in 0..n {
+            let pivot =
+                (column..n).max_by(|a, b| rows[*a][column].abs().cmp(&rows[*b][column].abs()))?;
+            if rows[pivot][column].0.is_zero() {
+                return None;
+            }
+            rows.swap(column, pivot);
+            let (upper, lower) = rows.split_at_mut(column + 1);
+            let pivot_row = &upper[column];
+            for row in lower {
+                let factor = &row[column] / &pivot_row[column];
+                for (value, pivot_value) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                    *value = &*value - &(&factor * pivot_value);
+                }
+            }
+        }
+        let mut solution = vec![Fixed::zero(); n];
+        for row in (0..n).rev() {
+            let sum = (row + 1..n).fold(rows[row][n].clone(), |sum, k| {
+                &sum - &(&rows[row][k] * &solution[k])
+            });
+            solution[row] = &sum / &rows[row][row];
+        }
+        let levelled_error = solution.pop()?;
+        Some((solution, levelled_error))
+    }
+
+    /// Maximises |error| on [a, b] by golden section search
+    fn refine(&self, coefficients: &[Fixed], mut a: Fixed, mut b: Fixed) -> Fixed {
+        let ratio =
+            Fixed::parse("0.618033988749894848204586834365638117720309179805762862135448622")
+                .unwrap();
+        let magnitude = |x: &Fixed| self.error(coefficients, x).abs();
+        for _ in 0..GOLDEN_ITERATIONS {
+            let step = &(&b - &a) * &ratio;
+            let c = &b - &step;
+            let d = &a + &step;
+            if magnitude(&c) >= magnitude(&d) {
+                b = d;
+            } else {
+                a = c;
+            }
+        }
+        &a + &(&(&b - &a) / &Fixed::from_int(2))
+    }
+
+    /// Finds the alternating extrema of the error, one per sign change segment
+    fn extrema(&self, coefficients: &[Fixed], count: usize) -> Option<Vec<(Fixed, Fixed)>> {
+        let n = GRID_POINTS_PER_COEFFICIENT * count;
+        let points: Vec<(Fixed, Fixed)> = (0..=n)
+            .map(|i| {
+                let x = self.grid(i, n);
+                let e = self.error(coefficients, &x);
+                (x, e)
+            })
+            .collect();
+
+        // best grid index per segment of equal sign
+        let mut segments: Vec<usize> = vec![];
+        for (i, (_, e)) in points.iter().enumerate() {
+            if e.0.is_zero() {
+                continue;
+            }
+            match segments.last() {
+                Some(&last) if points[last].1.is_negative() == e.is_negative() => {
+                    if e.abs() > points[last].1.abs() {
+                        *segments.last_mut()? = i;
+                    }
+                }
+                _ => segments.push(i),
+            }
+        }
+
+        let mut extrema: Vec<(Fixed, Fixed)> = segments
+            .into_iter()
+            .map(|i| {
+                let a = points[i.saturating_sub(1)].0.clone();
+                let b = points[(i + 1).min(n)].0.clone();
+                let x = if i == 0 || i == n {
+                    points[i].0.clone()
+                } else {
+                    self.refine(coefficients, a, b)
+                };
+                let e = self.error(coefficients, &x);
+                (x, e)
+            })
+            .collect();
+        if extrema.len() < count {
+            return None;
+        }
+        // drop the smaller end until exactly count alternating extrema remain
+        while extrema.len() > count {
+            let first = extrema.first()?.1.abs();
+            let last = extrema.last()?.1.abs();
+            if first < last {
+                extrema.remove(0);
+            } else {
+                extrema.pop();
+            }
+        }
+        Some(extrema)
+    }
+
+    fn run(&self, iterations: usize) -> Result<Vec<Fixed>, String> {
+        let count = self.powers.len() + 1;
+        let mut reference = self.chebyshev(count);
+        let levelled = Fixed::parse(LEVELLED).unwrap();
+        for iteration in 1..=iterations {
+            let (coefficients, levelled_error) =
+                self.solve(&reference).ok_or("singular reference system")?;
+            let extrema = self
+                .extrema(&coefficients, count)
+                .ok_or("error does not alternate, reduce the degree or the interval")?;
+            let max = extrema
+                .iter()
+                .map(|(_, e)| e.abs())
+                .max()
+                .unwrap_or(Fixed::zero());
+            let min = extrema
+                .iter()
+                .map(|(_, e)| e.abs())
+                .min()
+                .unwrap_or(Fixed::zero());
+            eprintln!(
+                "iteration {iteration}: levelled error {:.6e}, max error {:.6e}",
+                levelled_error.abs().to_f64(),
+                max.to_f64()
+            );
+            reference = extrema.into_iter().map(|(x, _)| x).collect();
+            if min.0.is_zero() {
+                continue;
+            }
+            if &(&max / &min) - &Fixed::one() < levelled {
+                return Ok(coefficients);
+            }
+        }
+        Err(format!("not levelled after {iterations} iterations"))
+    }
+
+    /// Maximum weighted error of the coefficients on a dense grid
+    fn max_error(&self, coefficients: &[Fixed]) -> Fixed {
+        (0..=VERIFY_POINTS)
+            .map(|i| self.error(coefficients, &self.grid(i, VERIFY_POINTS)).abs())
+            .max()
+            .unwrap_or(Fixed::zero())
+    }
+}
+
+struct Arguments {
+    remez: Remez,
+    name: String,
+    iterations: usize,
+}
+
+fn parse_arguments(args: &[String]) -> Result<Arguments, String> {
+    let [function, lower, upper, degree, options @ ..] = args else {
+        return Err("expected <function> <lower> <upper> <degree>".into());
+    };
+    let function = Function::parse(function).ok_or(format!("unknown function {function}"))?;
+    let lower = Fixed::parse(lower).ok_or(format!("invalid lower bound {lower}"))?;
+    let upper = Fixed::parse(upper).ok_or(format!("invalid upper bound {upper}"))?;
+    let degree: usize = degree
+        .parse()
+        .map_err(|_| format!("invalid degree {degree}"))?;
+    if lower >= upper {
+        return Err("lower bound must be less than upper bound".into());
+    }
+    if !function.is_defined(&lower) {
+        return Err("function is not defined on the interval".into());
+    }
+
+    let mut min_power = 0;
+    let mut relative = false;
+    let mut name = String::from("C");
+    let mut iterations = DEFAULT_ITERATIONS;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--relative" => relative = true,
+            "--min-power" | "--name" | "--iterations" => {
+                let value = options
+                    .next()
+                    .ok_or(format!("missing value for {option}"))?;
+                match option.as_str() {
+                    "--min-power" => {
+                        min_power = value
+                            .parse()
+                            .map_err(|_| format!("invalid power {value}"))?
+                    }
+                    "--name" => name = value.clone(),
+                    _ => {
+                        iterations = value
+                            .parse()
+                            .map_err(|_| format!("invalid iterations {value}"))?
+                    }
+                }
+            }
+            _ => return Err(format!("unknown option {option}")),
+        }
+    }
+    if min_power > degree {
+        return Err("min power must not exceed the degree".into());
+    }
+    Ok(Arguments {
+        remez: Remez {
+            function,
+            lower,
+            upper,
+            powers: (min_power..=degree).collect(),
+            relative,
+        },
+        name,
+        iterations,
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let arguments = match parse_arguments(&args) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("error: {message}");
+            eprintln!(
+                "usage: remez <exp|ln|ln1p|sqrt|exp-r|log-r> <lower> <upper> <degree> \
+                 [--min-power <k>] [--relative] [--name <prefix>] [--iterations <n>]"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let remez = &arguments.remez;
+    let coefficients = match remez.run(arguments.iterations) {
+        Ok(coefficients) => coefficients,
+        Err(message) => {
+            eprintln!("error: {message}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let rounded: Vec<Fixed> = coefficients
+        .iter()
+        .map(|c| c.round(OUTPUT_DIGITS))
+        .collect();
+    let max_error = remez.max_error(&rounded);
+    println!(
+        "// {} on [{}, {}], degree {}, {} error",
+        args[0],
+        remez.lower,
+        remez.upper,
+        remez.powers.last().unwrap_or(&0),
+        if remez.relative {
+            "relative"
+        } else {
+            "absolute"
+        }
+    );
+    for (power, c) in remez.powers.iter().zip(&rounded) {
+        println!(
+            "const {}{}: PreciseDecimal = pdec!(\"{}\");",
+            arguments.name, power, c
+        );
+    }
+    let log2 = max_error.to_f64().log2();
+    println!(
+        "// max error: {} (2^{:.2})",
+        max_error.to_decimal_string(OUTPUT_DIGITS + 4),
+        log2
+    );
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixed::ln2;
+    use pretty_assertions::assert_eq;
+
+    fn fixed(value: &str) -> Fixed {
+        Fixed::parse(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(
+            fixed("-1.25").to_string(),
+            "-1.250000000000000000000000000000000000"
+        );
+        assert_eq!(fixed("2.999").round(2).to_decimal_string(2), "3.00");
+        assert_eq!(Fixed::parse("1.2.3"), None);
+        assert_eq!(Fixed::parse("-"), None);
+        assert_eq!(fixed("0.5").round(0), fixed("1"));
+        assert_eq!(fixed("-0.5").round(0), fixed("-1"));
+    }
+
+    #[test]
+    fn test_exp_ln() {
+        assert_eq!(
+            exp(&Fixed::one()).to_string(),
+            "2.718281828459045235360287471352662497"
+        );
+        assert_eq!(
+            ln(&Fixed::from_int(10)).to_string(),
+            "2.302585092994045684017991454684364207"
+        );
+        assert_eq!(ln2().to_string(), "0.693147180559945309417232121458176568");
+        assert_eq!(
+            exp(&fixed("-50")).to_string(),
+            "0.000000000000000000000192874984796391"
+        );
+    }
+
+    #[test]
+    fn test_targets() {
+        // R(z) - 2 = z/6 - z^2/360 + ...
+        let z = fixed("0.0001");
+        let exp_r = Function::ExpR.evaluate(&z);
+        assert_eq!(exp_r.to_string(), "0.000016666638888955026289682957216622");
+        let log_r = Function::LogR.evaluate(&z);
+        // 2*atanh(0.01)/0.01 - 2
+        assert_eq!(log_r.to_string(), "0.000066670666952403176421510280844777");
+    }
+
+    #[test]
+    fn test_remez_exp_r() {
+        // regenerates P1..P5 of exponential.rs
+        let arguments = parse_arguments(
+            &["exp-r", "0", "0.120112240878645", "5", "--min-power", "1"].map(String::from),
+        )
+        .unwrap();
+        let coefficients = arguments.remez.run(DEFAULT_ITERATIONS).unwrap();
+        let rounded: Vec<Fixed> = coefficients
+            .iter()
+            .map(|c| c.round(OUTPUT_DIGITS))
+            .collect();
+        assert!(rounded[0].to_string().starts_with("0.16666666666666"));
+        assert!(
+            arguments.remez.max_error(&rounded) < fixed("0.000000000000000001734723475976807094")
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(parse_arguments(&["exp", "1", "0", "3"].map(String::from)).is_err());
+        assert!(parse_arguments(&["ln", "0", "1", "3"].map(String::from)).is_err());
+        assert!(parse_arguments(&["sin", "0", "1", "3"].map(String::from)).is_err());
+        assert!(
+            parse_arguments(&["exp", "0", "1", "3", "--min-power", "4"].map(String::from)).is_err()
+        );
+    }
+}
@@ -0,0 +1,519 @@
+// Bonding curves
+//
+// Method:
+//      A bonding curve defines the spot price p(s) of a token as a function of its supply s.
+//      The reserve backing a supply s is the integral
+//              I(s) = ∫_0^s p(x) dx
+//      and the supply backed by a reserve R is the inverse integral I^-1(R).
+//
+//      1. Linear:        p(s) = c + m*s
+//                        I(s) = c*s + m*s^2/2
+//                        I^-1(R) = 2*R / (c + sqrt(c^2 + 2*m*R))
+//      2. Power:         p(s) = a * s^n
+//                        I(s) = a * s^(n+1) / (n+1)
+//                        I^-1(R) = ((n+1) * R / a)^(1/(n+1))
+//      3. Exponential:   p(s) = a * e^(k*s)
+//                        I(s) = a/k * (e^(k*s) - 1)
+//                        I^-1(R) = ln(1 + k*R/a) / k
+//      4. Sigmoid:       p(s) = L * sigmoid(k*(s - s0))
+//                        I(s) = L/k * (softplus(k*(s - s0)) - softplus(-k*s0))
+//                        I^-1(R) = s0 + ln(e^y - 1) / k,  y = k*R/L + softplus(-k*s0)
+//
+// Rounding:
+//      Every curve bounds the absolute error of its integral. Trades are calculated from these bounds:
+//              buy_cost(s, a)    = upper(I(s + a)) - lower(I(s))     rounded up
+//              sell_return(s, a) = lower(I(s)) - upper(I(s - a))     rounded down
+//      buy_amount(s, d) starts from I^-1(I(s) + d) - s and reduces the amount until buy_cost does not
+//      exceed the deposit d, so a buyer never receives more tokens than paid for.
+//
+// Accuracy:
+//      Linear:       (m + 3) * 10^-36, exact up to the truncation of s*s (scaled by m), m*s^2, /2 and c*s
+//      Power:        a/(n+1) * error_pow(s, n+1) + (a + 3) * 10^-36, error of pow
+//      Exponential:  a/k * error_exp(k*s) + 4 * 10^-36, error of exp
+//      Sigmoid:      2 * L/k * 6*10^-18 + 4 * 10^-36, error of softplus
+
+use crate::error_estimation::{error_exp, error_pow, ULP};
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::logarithm::LogarithmPreciseDecimal;
+use crate::logistic::{LogisticPreciseDecimal, ERROR_SOFTPLUS};
+use crate::power::PowerPreciseDecimal;
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, PreciseDecimal,
+};
+use radix_common::ScryptoSbor;
+use radix_common_derive::pdec;
+
+const MAX_ROUNDING_ITERATIONS: usize = 10;
+
+/// Spot price, integral and inverse integral of a monotonically increasing price curve
+pub trait BondingCurve {
+    /// Spot price at `supply`
+    fn price(&self, supply: PreciseDecimal) -> Option<PreciseDecimal>;
+
+    /// Reserve backing `supply`, the integral of the price from zero to `supply`
+    fn integral(&self, supply: PreciseDecimal) -> Option<PreciseDecimal>;
+
+    /// Upper bound of the absolute error of `integral(supply)`
+    fn integral_error(&self, supply: PreciseDecimal) -> Option<PreciseDecimal>;
+
+    /// Supply backed by `reserve`, the inverse of `integral`
+    fn inverse_integral(&self, reserve: PreciseDecimal) -> Option<PreciseDecimal>;
+
+    /// Cost of buying `amount` tokens at `supply`, rounded up
+    fn buy_cost(&self, supply: PreciseDecimal, amount: PreciseDecimal) -> Option<PreciseDecimal> {
+        if amount.is_negative() {
+            return None;
+        }
+        let new_supply = supply.checked_add(amount)?;
+        let upper = self
+            .integral(new_supply)?
+            .checked_add(self.integral_error(new_supply)?)?;
+        let lower = self
+            .integral(supply)?
+            .checked_sub(self.integral_error(supply)?)?;
+        Some(upper.checked_sub(lower)?.max(PreciseDecimal::ZERO))
+    }
+
+    /// Return of selling `amount` tokens at `supply`, rounded down
+    fn sell_return(
+        &self,
+        supply: PreciseDecimal,
+        amount: PreciseDecimal,
+    ) -> Option<PreciseDecimal> {
+        if amount.is_negative() || amount > supply {
+            return None;
+        }
+        let new_supply = supply.checked_sub(amount)?;
+        let lower = self
+            .integral(supply)?
+            .checked_sub(self.integral_error(supply)?)?;
+        let upper = self
+            .integral(new_supply)?
+            .checked_add(self.integral_error(new_supply)?)?;
+        Some(lower.checked_sub(upper)?.max(PreciseDecimal::ZERO))
+    }
+
+    /// Number of tokens bought at `supply` for `deposit`, rounded down
+    fn buy_amount(
+        &self,
+        supply: PreciseDecimal,
+        deposit: PreciseDecimal,
+    ) -> Option<PreciseDecimal> {
+        if deposit.is_negative() {
+            return None;
+        }
+        let reserve = self.integral(supply)?.checked_add(deposit)?;
+        let mut amount = self.inverse_integral(reserve)?.checked_sub(supply)?;
+        for _ in 0..MAX_ROUNDING_ITERATIONS {
+            if !amount.is_positive() {
+                return Some(PreciseDecimal::ZERO);
+            }
+            let excess = self.buy_cost(supply, amount)?.checked_sub(deposit)?;
+            if !excess.is_positive() {
+                return Some(amount);
+            }
+            // the price at the end of the interval is the highest, overcorrecting twice the excess
+            let price = self.price(supply.checked_add(amount)?)?;
+            let correction = excess.checked_mul(pdec!(2))?.checked_div(price)?;
+            amount = amount.checked_sub(correction.checked_add(ULP)?)?;
+        }
+        None
+    }
+}
+
+/// Linear curve p(s) = c + m*s
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinearCurve {
+    intercept: PreciseDecimal,
+    slope: PreciseDecimal,
+}
+
+/// Power curve p(s) = a * s^n
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PowerCurve {
+    coefficient: PreciseDecimal,
+    exponent: PreciseDecimal,
+}
+
+/// Exponential curve p(s) = a * e^(k*s)
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExponentialCurve {
+    coefficient: PreciseDecimal,
+    rate: PreciseDecimal,
+}
+
+/// Sigmoid curve p(s) = L * sigmoid(k*(s - s0))
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SigmoidCurve {
+    max_price: PreciseDecimal,
+    steepness: PreciseDecimal,
+    midpoint: PreciseDecimal,
+}
+
+impl LinearCurve {
+    /// Creates a linear curve, returning None for negative parameters or a constant zero price
+    pub fn new(intercept: PreciseDecimal, slope: PreciseDecimal) -> Option<Self> {
+        if intercept.is_negative()
+            || slope.is_negative()
+            || (intercept.is_zero() && slope.is_zero())
+        {
+            return None;
+        }
+        Some(Self { intercept, slope })
+    }
+}
+
+impl PowerCurve {
+    /// Creates a power curve, returning None for a non-positive coefficient or a negative exponent
+    pub fn new(coefficient: PreciseDecimal, exponent: PreciseDecimal) -> Option<Self> {
+        if !coefficient.is_positive() || exponent.is_negative() {
+            return None;
+        }
+        Some(Self {
+            coefficient,
+            exponent,
+        })
+    }
+}
+
+impl ExponentialCurve {
+    /// Creates an exponential curve, returning None for non-positive parameters
+    pub fn new(coefficient: PreciseDecimal, rate: PreciseDecimal) -> Option<Self> {
+        if !coefficient.is_positive() || !rate.is_positive() {
+            return None;
+        }
+        Some(Self { coefficient, rate })
+    }
+}
+
+impl SigmoidCurve {
+    /// Creates a sigmoid curve, returning None for a non-positive maximum price or steepness
+    pub fn new(
+        max_price: PreciseDecimal,
+        steepness: PreciseDecimal,
+        midpoint: PreciseDecimal,
+    ) -> Option<Self> {
+        if !max_price.is_positive() || !steepness.is_positive() {
+            return None;
+        }
+        Some(Self {
+            max_price,
+            steepness,
+            midpoint,
+        })
+    }
+
+    /// Calculates softplus(k*(s - s0))
+    fn softplus_at(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        self.steepness
+            .checked_mul(supply.checked_sub(self.midpoint)?)?
+            .softplus()
+    }
+}
+
+impl BondingCurve for LinearCurve {
+    fn price(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        if supply.is_negative() {
+            return None;
+        }
+        self.intercept.checked_add(self.slope.checked_mul(supply)?)
+    }
+
+    fn integral(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        if supply.is_negative() {
+            return None;
+        }
+        let quadratic = self
+            .slope
+            .checked_mul(supply.checked_mul(supply)?)?
+            .checked_div(pdec!(2))?;
+        self.intercept.checked_mul(supply)?.checked_add(quadratic)
+    }
+
+    fn integral_error(&self, _supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        self.slope.checked_add(pdec!(3))?.checked_mul(ULP)
+    }
+
+    fn inverse_integral(&self, reserve: PreciseDecimal) -> Option<PreciseDecimal> {
+        if reserve.is_negative() {
+            return None;
+        }
+        // 2*R / (c + sqrt(c^2 + 2*m*R)) avoids the cancellation of (sqrt(c^2 + 2*m*R) - c) / m
+        let double_reserve = reserve.checked_mul(pdec!(2))?;
+        let discriminant = self
+            .intercept
+            .checked_mul(self.intercept)?
+            .checked_add(self.slope.checked_mul(double_reserve)?)?;
+        let denominator = self.intercept.checked_add(discriminant.checked_sqrt()?)?;
+        if denominator.is_zero() {
+            return Some(PreciseDecimal::ZERO);
+        }
+        double_reserve.checked_div(denominator)
+    }
+}
+
+impl BondingCurve for PowerCurve {
+    fn price(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        if supply.is_negative() {
+            return None;
+        }
+        self.coefficient.checked_mul(supply.pow(self.exponent)?)
+    }
+
+    fn integral(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        if supply.is_negative() {
+            return None;
+        }
+        let exponent = self.exponent.checked_add(PreciseDecimal::ONE)?;
+        self.coefficient
+            .checked_mul(supply.pow(exponent)?)?
+            .checked_div(exponent)
+    }
+
+    fn integral_error(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        let exponent = self.exponent.checked_add(PreciseDecimal::ONE)?;
+        self.coefficient
+            .checked_mul(error_pow(supply, exponent)?)?
+            .checked_div(exponent)?
+            .checked_add(self.coefficient.checked_add(pdec!(3))?.checked_mul(ULP)?)
+    }
+
+    fn inverse_integral(&self, reserve: PreciseDecimal) -> Option<PreciseDecimal> {
+        if reserve.is_negative() {
+            return None;
+        }
+        let exponent = self.exponent.checked_add(PreciseDecimal::ONE)?;
+        exponent
+            .checked_mul(reserve)?
+            .checked_div(self.coefficient)?
+            .pow(PreciseDecimal::ONE.checked_div(exponent)?)
+    }
+}
+
+impl BondingCurve for ExponentialCurve {
+    fn price(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        if supply.is_negative() {
+            return None;
+        }
+        self.coefficient
+            .checked_mul(self.rate.checked_mul(supply)?.exp()?)
+    }
+
+    fn integral(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        if supply.is_negative() {
+            return None;
+        }
+        let exp = self.rate.checked_mul(supply)?.exp()?;
+        self.coefficient
+            .checked_mul(exp.checked_sub(PreciseDecimal::ONE)?)?
+            .checked_div(self.rate)
+    }
+
+    fn integral_error(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        self.coefficient
+            .checked_mul(error_exp(self.rate.checked_mul(supply)?)?)?
+            .checked_div(self.rate)?
+            .checked_add(ULP.checked_mul(pdec!(4))?)
+    }
+
+    fn inverse_integral(&self, reserve: PreciseDecimal) -> Option<PreciseDecimal> {
+        if reserve.is_negative() {
+            return None;
+        }
+        self.rate
+            .checked_mul(reserve)?
+            .checked_div(self.coefficient)?
+            .checked_add(PreciseDecimal::ONE)?
+            .ln()?
+            .checked_div(self.rate)
+    }
+}
+
+impl BondingCurve for SigmoidCurve {
+    fn price(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        if supply.is_negative() {
+            return None;
+        }
+        self.max_price.checked_mul(
+            self.steepness
+                .checked_mul(supply.checked_sub(self.midpoint)?)?
+                .sigmoid()?,
+        )
+    }
+
+    fn integral(&self, supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        if supply.is_negative() {
+            return None;
+        }
+        let softplus_diff = self
+            .softplus_at(supply)?
+            .checked_sub(self.softplus_at(PreciseDecimal::ZERO)?)?;
+        self.max_price
+            .checked_mul(softplus_diff)?
+            .checked_div(self.steepness)
+    }
+
+    fn integral_error(&self, _supply: PreciseDecimal) -> Option<PreciseDecimal> {
+        self.max_price
+            .checked_mul(ERROR_SOFTPLUS.checked_mul(pdec!(2))?)?
+            .checked_div(self.steepness)?
+            .checked_add(ULP.checked_mul(pdec!(4))?)
+    }
+
+    fn inverse_integral(&self, reserve: PreciseDecimal) -> Option<PreciseDecimal> {
+        if reserve.is_negative() {
+            return None;
+        }
+        if reserve.is_zero() {
+            return Some(PreciseDecimal::ZERO);
+        }
+        let y = self
+            .steepness
+            .checked_mul(reserve)?
+            .checked_div(self.max_price)?
+            .checked_add(self.softplus_at(PreciseDecimal::ZERO)?)?;
+        // ln(e^y - 1) = y + ln(1 - e^-y) avoids the overflow of e^y
+        let one_minus_exp = PreciseDecimal::ONE.checked_sub(y.checked_neg()?.exp()?)?;
+        let shifted = y.checked_add(one_minus_exp.ln()?)?;
+        self.midpoint
+            .checked_add(shifted.checked_div(self.steepness)?)
+            .map(|supply| supply.max(PreciseDecimal::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    fn linear() -> LinearCurve {
+        LinearCurve::new(pdec!("0.5"), pdec!("0.001")).unwrap()
+    }
+
+    fn power() -> PowerCurve {
+        PowerCurve::new(pdec!("0.0002"), pdec!("1.5")).unwrap()
+    }
+
+    fn exponential() -> ExponentialCurve {
+        ExponentialCurve::new(pdec!("0.1"), pdec!("0.001")).unwrap()
+    }
+
+    fn sigmoid() -> SigmoidCurve {
+        SigmoidCurve::new(pdec!(10), pdec!("0.01"), pdec!(1000)).unwrap()
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        assert_eq!(LinearCurve::new(pdec!(0), pdec!(0)), None);
+        assert_eq!(LinearCurve::new(pdec!(-1), pdec!(1)), None);
+        assert_eq!(LinearCurve::new(pdec!(1), pdec!(-1)), None);
+        assert_eq!(PowerCurve::new(pdec!(0), pdec!(2)), None);
+        assert_eq!(PowerCurve::new(pdec!(1), pdec!(-1)), None);
+        assert_eq!(ExponentialCurve::new(pdec!(1), pdec!(0)), None);
+        assert_eq!(ExponentialCurve::new(pdec!(-1), pdec!(1)), None);
+        assert_eq!(SigmoidCurve::new(pdec!(0), pdec!(1), pdec!(0)), None);
+        assert_eq!(SigmoidCurve::new(pdec!(1), pdec!(0), pdec!(0)), None);
+    }
+
+    #[test]
+    fn test_negative_supply() {
+        assert_eq!(linear().price(pdec!(-1)), None);
+        assert_eq!(power().integral(pdec!(-1)), None);
+        assert_eq!(exponential().inverse_integral(pdec!(-1)), None);
+        assert_eq!(sigmoid().buy_cost(pdec!(0), pdec!(-1)), None);
+        assert_eq!(sigmoid().sell_return(pdec!(10), pdec!(11)), None);
+    }
+
+    #[test]
+    fn test_linear_exact() {
+        let curve = linear();
+        assert_eq!(curve.price(pdec!(1000)), Some(pdec!("1.5")));
+        assert_eq!(curve.integral(pdec!(1000)), Some(pdec!(1000)));
+        assert_eq!(curve.inverse_integral(pdec!(1000)), Some(pdec!(1000)));
+        assert_eq!(curve.integral(pdec!(0)), Some(pdec!(0)));
+        assert_eq!(curve.inverse_integral(pdec!(0)), Some(pdec!(0)));
+    }
+
+    #[test_case(pdec!(1000), pdec!("2529.822128134703465599114835546174826975"), pdec!("0.000000000001"); "power")]
+    #[test_case(pdec!(0), pdec!(0), pdec!("0.000000000000000000000000000000000004"); "power zero")]
+    fn test_power_integral(
+        supply: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let integral = power().integral(supply).unwrap();
+        assert!((integral - target_result).checked_abs().unwrap() <= max_error);
+        assert!(power().integral_error(supply).unwrap() <= max_error);
+    }
+
+    #[test_case(exponential(), pdec!(1000), pdec!("171.828182845904523536028747135266249775"); "exponential")]
+    fn test_exponential_integral(
+        curve: ExponentialCurve,
+        supply: PreciseDecimal,
+        target_result: PreciseDecimal,
+    ) {
+        let integral = curve.integral(supply).unwrap();
+        let max_error = curve.integral_error(supply).unwrap();
+        assert!((integral - target_result).checked_abs().unwrap() <= max_error);
+    }
+
+    #[test_case(pdec!(0), pdec!(0); "zero")]
+    #[test_case(pdec!(500), pdec!("6.669949589901203969647199903334969518"); "below midpoint")]
+    #[test_case(pdec!(1000), pdec!("693.101781660728444770462633628869462478"); "midpoint")]
+    #[test_case(pdec!(3000), pdec!("19999.954603161936755667611215409675693281"); "above midpoint")]
+    fn test_sigmoid_integral(supply: PreciseDecimal, target_result: PreciseDecimal) {
+        let curve = sigmoid();
+        let integral = curve.integral(supply).unwrap();
+        let max_error = curve.integral_error(supply).unwrap();
+        assert!((integral - target_result).checked_abs().unwrap() <= max_error);
+    }
+
+    #[test_case(&linear(); "linear")]
+    #[test_case(&power(); "power")]
+    #[test_case(&exponential(); "exponential")]
+    #[test_case(&sigmoid(); "sigmoid")]
+    fn test_inverse_integral(curve: &dyn BondingCurve) {
+        for supply in [pdec!(1), pdec!(100), pdec!("1234.5"), pdec!(5000)] {
+            let reserve = curve.integral(supply).unwrap();
+            let inverse = curve.inverse_integral(reserve).unwrap();
+            let max_error = supply * pdec!("0.0000000000001");
+            assert!((inverse - supply).checked_abs().unwrap() <= max_error);
+        }
+    }
+
+    #[test_case(&linear(); "linear")]
+    #[test_case(&power(); "power")]
+    #[test_case(&exponential(); "exponential")]
+    #[test_case(&sigmoid(); "sigmoid")]
+    fn test_rounding_direction(curve: &dyn BondingCurve) {
+        let supply = pdec!(1000);
+        for deposit in [pdec!("0.000001"), pdec!(1), pdec!("123.456"), pdec!(10000)] {
+            let amount = curve.buy_amount(supply, deposit).unwrap();
+            let cost = curve.buy_cost(supply, amount).unwrap();
+            assert!(cost <= deposit);
+            assert!(deposit - cost <= pdec!("0.000000000001"));
+            // selling the tokens right away never returns more than paid
+            let sell = curve.sell_return(supply + amount, amount).unwrap();
+            assert!(sell <= cost);
+        }
+    }
+
+    #[test]
+    fn test_buy_cost_upper_bound() {
+        // exact cost of buying 500 tokens at a supply of 1000 on the exponential curve: 100 * (e^1.5 - e)
+        let exact = pdec!("176.340724187901958724176798876661332124");
+        let cost = exponential().buy_cost(pdec!(1000), pdec!(500)).unwrap();
+        assert!(cost >= exact);
+        assert!(cost - exact <= pdec!("0.000000000000005"));
+        let sell = exponential().sell_return(pdec!(1500), pdec!(500)).unwrap();
+        assert!(sell <= exact);
+        assert!(exact - sell <= pdec!("0.000000000000005"));
+    }
+
+    #[test]
+    fn test_buy_amount_zero() {
+        assert_eq!(linear().buy_amount(pdec!(1000), pdec!(0)), Some(pdec!(0)));
+        assert_eq!(linear().buy_amount(pdec!(1000), pdec!(-1)), None);
+    }
+}
@@ -0,0 +1,293 @@
+// Exponential decay and exponential moving averages
+//
+// Method:
+//      1. Exponential decay with time constant tau:
+//              decay(v, dt, tau) = v * e^(-dt/tau)
+//
+//      2. Half-life decay with half-life h:
+//              decay(v, dt, h) = v * 2^(-dt/h)
+//                              = v * 2^-k * e^(-f*ln2)     with dt/h = k + f, k integer, 0 <= f < 1
+//         Scaling by 2^-k is a shift of the subunits truncated towards zero, so whole half-lives are exact
+//         up to the truncation of the last subunit.
+//
+//      3. Exponential moving average:
+//              ema = prev * e^(-dt/tau) + x * (1 - e^(-dt/tau))
+//                  = x + (prev - x) * e^(-dt/tau)                   (single multiplication)
+//
+// Special cases:
+//      1. dt == 0 leaves the value unchanged.
+//      2. dt so large that dt/tau overflows or exp underflows (dt/tau > 82) yields a factor of zero,
+//         i.e. the value has fully decayed and the EMA equals the latest observation.
+//      3. Negative dt and non-positive tau / half-life are invalid and return None.
+//
+// Accuracy:
+//      The decay factor inherits the error of ExponentialPreciseDecimal::exp for the reduced argument,
+//      which is at most 2^-59 because the exponent is never positive.
+
+use crate::exponential::{ExponentialPreciseDecimal, LN2};
+use num_traits::ToPrimitive;
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, PreciseDecimal, I256,
+};
+
+/// Calculates the decay factor `e^(-dt/tau)`
+pub(crate) fn decay_factor(dt: PreciseDecimal, tau: PreciseDecimal) -> Option<PreciseDecimal> {
+    if dt.is_negative() || !tau.is_positive() {
+        return None;
+    }
+    match dt.checked_div(tau) {
+        Some(ratio) => ratio.checked_neg()?.exp(),
+        None => Some(PreciseDecimal::ZERO), // dt/tau overflows, fully decayed
+    }
+}
+
+/// Calculates the exponential decay `value * e^(-dt/tau)` with time constant `tau`
+pub fn exponential_decay(
+    value: PreciseDecimal,
+    dt: PreciseDecimal,
+    tau: PreciseDecimal,
+) -> Option<PreciseDecimal> {
+    value.checked_mul(decay_factor(dt, tau)?)
+}
+
+/// Calculates the half-life decay `value * 2^(-dt/half_life)`
+pub fn half_life_decay(
+    value: PreciseDecimal,
+    dt: PreciseDecimal,
+    half_life: PreciseDecimal,
+) -> Option<PreciseDecimal> {
+    if dt.is_negative() || !half_life.is_positive() {
+        return None;
+    }
+    let one_subunits = PreciseDecimal::ONE.precise_subunits();
+    let ratio = match dt.checked_div(half_life) {
+        Some(ratio) => ratio,
+        None => return Some(PreciseDecimal::ZERO), // dt/half_life overflows, fully decayed
+    };
+
+    // dt/half_life = k + f
+    let k = ratio.precise_subunits().checked_div(one_subunits)?;
+    let f = ratio.checked_sub(PreciseDecimal::from_precise_subunits(
+        k.checked_mul(one_subunits)?,
+    ))?;
+    // 2^-256 shifts out every subunit of a PreciseDecimal
+    let k: u32 = k.to_u32().filter(|k| *k < 256).unwrap_or(256);
+    if k == 256 {
+        return Some(PreciseDecimal::ZERO);
+    }
+
+    // 2^-f = e^(-f*ln2)
+    let decayed = value.checked_mul(f.checked_mul(LN2)?.checked_neg()?.exp()?)?;
+    let mut subunits = decayed.precise_subunits().0;
+    if subunits.is_negative() {
+        // the arithmetic shift rounds towards -∞, a bias of 2^k - 1 truncates towards zero instead
+        subunits = subunits.checked_add(I256::MAX.0.checked_shr(255u32.checked_sub(k)?)?)?;
+    }
+    Some(PreciseDecimal::from_precise_subunits(I256(
+        subunits.checked_shr(k)?,
+    )))
+}
+
+/// Calculates the exponential moving average `prev * e^(-dt/tau) + observation * (1 - e^(-dt/tau))`
+pub fn ema(
+    prev: PreciseDecimal,
+    observation: PreciseDecimal,
+    dt: PreciseDecimal,
+    tau: PreciseDecimal,
+) -> Option<PreciseDecimal> {
+    let factor = decay_factor(dt, tau)?;
+    let delta = prev.checked_sub(observation)?.checked_mul(factor)?;
+    observation.checked_add(delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common_derive::pdec;
+    use test_case::test_case;
+
+    #[test]
+    fn test_exponential_decay_zero_dt() {
+        assert_eq!(
+            exponential_decay(pdec!(100), pdec!(0), pdec!(60)),
+            Some(pdec!(100))
+        );
+    }
+
+    #[test]
+    fn test_exponential_decay_one_tau() {
+        assert_eq!(
+            exponential_decay(pdec!(1), pdec!(60), pdec!(60)),
+            pdec!(-1).exp()
+        );
+    }
+
+    #[test]
+    fn test_exponential_decay_large_dt() {
+        assert_eq!(
+            exponential_decay(pdec!(100), pdec!(83), pdec!(1)),
+            Some(pdec!(0))
+        );
+        assert_eq!(
+            exponential_decay(
+                pdec!(100),
+                PreciseDecimal::MAX,
+                pdec!("0.000000000000000001")
+            ),
+            Some(pdec!(0))
+        );
+    }
+
+    #[test]
+    fn test_exponential_decay_invalid() {
+        assert_eq!(exponential_decay(pdec!(100), pdec!(-1), pdec!(60)), None);
+        assert_eq!(exponential_decay(pdec!(100), pdec!(1), pdec!(0)), None);
+        assert_eq!(exponential_decay(pdec!(100), pdec!(1), pdec!(-60)), None);
+    }
+
+    #[test]
+    fn test_half_life_decay_whole_half_lives() {
+        assert_eq!(
+            half_life_decay(pdec!(100), pdec!(0), pdec!(3600)),
+            Some(pdec!(100))
+        );
+        assert_eq!(
+            half_life_decay(pdec!(100), pdec!(3600), pdec!(3600)),
+            Some(pdec!(50))
+        );
+        assert_eq!(
+            half_life_decay(pdec!(100), pdec!(7200), pdec!(3600)),
+            Some(pdec!(25))
+        );
+        assert_eq!(
+            half_life_decay(pdec!(100), pdec!(36000), pdec!(3600)),
+            Some(pdec!("0.09765625"))
+        );
+    }
+
+    #[test]
+    fn test_half_life_decay_negative() {
+        assert_eq!(
+            half_life_decay(pdec!(-100), pdec!(7200), pdec!(3600)),
+            Some(pdec!(-25))
+        );
+        assert_eq!(
+            half_life_decay(pdec!(-100), pdec!("2.5"), pdec!(1)),
+            half_life_decay(pdec!(100), pdec!("2.5"), pdec!(1)).map(|decayed| -decayed)
+        );
+        assert_eq!(
+            half_life_decay(
+                pdec!("-0.000000000000000000000000000000000003"),
+                pdec!(1),
+                pdec!(1)
+            ),
+            Some(pdec!("-0.000000000000000000000000000000000001"))
+        );
+        assert_eq!(
+            half_life_decay(
+                pdec!("-0.000000000000000000000000000000000001"),
+                pdec!(1),
+                pdec!(1)
+            ),
+            Some(pdec!(0))
+        );
+    }
+
+    #[test]
+    fn test_half_life_decay_large_dt() {
+        assert_eq!(
+            half_life_decay(pdec!(100), pdec!(256), pdec!(1)),
+            Some(pdec!(0))
+        );
+        assert_eq!(
+            half_life_decay(pdec!(100), pdec!(1000000), pdec!(1)),
+            Some(pdec!(0))
+        );
+        assert_eq!(
+            half_life_decay(
+                pdec!(100),
+                PreciseDecimal::MAX,
+                pdec!("0.000000000000000001")
+            ),
+            Some(pdec!(0))
+        );
+    }
+
+    #[test]
+    fn test_half_life_decay_invalid() {
+        assert_eq!(half_life_decay(pdec!(100), pdec!(-1), pdec!(60)), None);
+        assert_eq!(half_life_decay(pdec!(100), pdec!(1), pdec!(0)), None);
+        assert_eq!(half_life_decay(pdec!(100), pdec!(1), pdec!(-60)), None);
+    }
+
+    #[test_case(pdec!(1), pdec!(2), pdec!(0.5), pdec!(0.840896415253714543031125476233214895), pdec!(0.000000000000000002); "quarter half-life")]
+    #[test_case(pdec!(1), pdec!(4), pdec!(5), pdec!(0.420448207626857271515562738116607447), pdec!(0.000000000000000002); "one and a quarter half-lives")]
+    #[test_case(pdec!(1000), pdec!(3), pdec!(10), pdec!(99.212565748012467171981602454519266274), pdec!(0.000000000000002); "three and a third half-lives")]
+    fn test_half_life_decay_error(
+        value: PreciseDecimal,
+        half_life: PreciseDecimal,
+        dt: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let result = half_life_decay(value, dt, half_life).unwrap();
+        let error = (result - target_result).checked_abs().unwrap();
+        assert!(error <= max_error);
+    }
+
+    #[test]
+    fn test_ema_zero_dt() {
+        assert_eq!(
+            ema(pdec!(10), pdec!(20), pdec!(0), pdec!(60)),
+            Some(pdec!(10))
+        );
+    }
+
+    #[test]
+    fn test_ema_large_dt() {
+        assert_eq!(
+            ema(pdec!(10), pdec!(20), pdec!(10000), pdec!(60)),
+            Some(pdec!(20))
+        );
+        assert_eq!(
+            ema(
+                pdec!(10),
+                pdec!(20),
+                PreciseDecimal::MAX,
+                pdec!("0.000000000000000001")
+            ),
+            Some(pdec!(20))
+        );
+    }
+
+    #[test]
+    fn test_ema_equal_values() {
+        assert_eq!(
+            ema(pdec!(20), pdec!(20), pdec!(30), pdec!(60)),
+            Some(pdec!(20))
+        );
+    }
+
+    #[test]
+    fn test_ema_invalid() {
+        assert_eq!(ema(pdec!(10), pdec!(20), pdec!(-1), pdec!(60)), None);
+        assert_eq!(ema(pdec!(10), pdec!(20), pdec!(1), pdec!(0)), None);
+    }
+
+    #[test_case(pdec!(10), pdec!(20), pdec!(60), pdec!(60), pdec!(16.321205588285576784044762298385391325), pdec!(0.000000000000000002); "one tau")]
+    #[test_case(pdec!(100), pdec!(50), pdec!(1), pdec!(10), pdec!(95.241870901797978658212452972321831059), pdec!(0.0000000000000001); "tenth of tau")]
+    #[test_case(pdec!(0), pdec!(1), pdec!(300), pdec!(60), pdec!(0.993262053000914532903363951576851576), pdec!(0.000000000000000002); "five tau")]
+    fn test_ema_error(
+        prev: PreciseDecimal,
+        observation: PreciseDecimal,
+        dt: PreciseDecimal,
+        tau: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let result = ema(prev, observation, dt, tau).unwrap();
+        let error = (result - target_result).checked_abs().unwrap();
+        assert!(error <= max_error);
+    }
+}
@@ -0,0 +1,172 @@
+// Maximum approximation errors of exp, ln and pow
+//
+// Method:
+//      1. exp reduces its argument to x = k*ln2 + r with |r| <= ln2/2 and approximates e^r with an
+//         error bound by 2^-59, which is scaled back with 2^k:
+//              error_exp(x) = 2^k * 2^-59 = 2^(k - 59)             k = round(x / ln2)
+//
+//      2. ln approximates log(1+f) with an error bound by 2^-58.45 independent of its argument:
+//              error_ln = 2^-58.45 ~ 2.6*10^-18
+//
+//      3. pow calculates x^y = e^(ln(x) * y), the error of ln is scaled by y and passed through exp,
+//         which for |error_ln * y| << 1 can be separated with e^(n + error) ~ e^n + e^n * error:
+//              error_pow(x, y) = error_exp(ln(x) * y) + e^(ln(x) * y) * error_ln * |y|
+//
+// Special cases:
+//      1. exp(x) for x < -82 returns zero, the error is the exact result e^x < e^-82.
+//      2. pow returns exact results for y == 0, y == 1, x == 1 and x == 0, therefore without error.
+//      3. Arguments for which pow is undefined return None.
+//
+// Accuracy:
+//      Every bound is rounded up to the next multiple of 10^-36. The formulas are the same as error_exp,
+//      error_ln and error_pow of python/scryptomath.py, but k and e^(ln(x) * y) are taken from the
+//      truncated arguments exp actually evaluates, which can differ by a factor of 2 right at the
+//      boundary of the argument reduction.
+
+use crate::exponential::{ExponentialPreciseDecimal, HALF_NEGATIVE, HALF_POSITIVE, INVLN2};
+use crate::logarithm::LogarithmPreciseDecimal;
+use num_traits::ToPrimitive;
+use radix_common::math::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, PreciseDecimal, I256};
+use radix_common_derive::pdec;
+
+const ERROR_LN: PreciseDecimal = pdec!("0.000000000000000002539783827598718036"); // 2^-58.45 rounded up
+const ERROR_EXP_UNDERFLOW: PreciseDecimal = pdec!("0.000000000000000000000000000000000003"); // e^-82 rounded up
+pub(crate) const ULP: PreciseDecimal = pdec!("0.000000000000000000000000000000000001");
+
+/// Maximum approximation error of `exp(x)` given by `2^(k - 59)` with k determined by the argument reduction
+pub fn error_exp(x: PreciseDecimal) -> Option<PreciseDecimal> {
+    if x < pdec!(-82) {
+        return Some(ERROR_EXP_UNDERFLOW);
+    }
+    let one_subunits = PreciseDecimal::ONE.precise_subunits();
+    let signed_half = if x.is_negative() {
+        HALF_NEGATIVE
+    } else {
+        HALF_POSITIVE
+    };
+    let k = INVLN2.checked_mul(x)?.checked_add(signed_half)?;
+    let shift = k
+        .precise_subunits()
+        .checked_div(one_subunits)?
+        .to_i32()?
+        .checked_sub(59)?;
+    let error_subunits = if shift < 0 {
+        // ceil(10^36 / 2^s) = floor((10^36 - 1) / 2^s) + 1, shifts beyond 255 leave zero
+        let shift = shift.unsigned_abs().min(255);
+        let truncated = I256(one_subunits.checked_sub(I256::ONE)?.0.checked_shr(shift)?);
+        truncated.checked_add(I256::ONE)?
+    } else {
+        I256(one_subunits.0.checked_shl(shift.unsigned_abs())?)
+    };
+    Some(PreciseDecimal::from_precise_subunits(error_subunits))
+}
+
+/// Maximum approximation error of `ln(x)` given by the constant `2^-58.45`
+pub fn error_ln() -> PreciseDecimal {
+    ERROR_LN
+}
+
+/// Maximum approximation error of `pow(x, y)` given by `error_exp(ln(x) * y) + e^(ln(x) * y) * error_ln * |y|`
+pub fn error_pow(x: PreciseDecimal, y: PreciseDecimal) -> Option<PreciseDecimal> {
+    if y.is_zero() || y == PreciseDecimal::ONE || x == PreciseDecimal::ONE {
+        return Some(PreciseDecimal::ZERO);
+    }
+    if x.is_zero() {
+        // 0^y is zero for positive y and undefined otherwise
+        return y.is_positive().then_some(PreciseDecimal::ZERO);
+    }
+    if x.is_negative() {
+        // the magnitude of the result is |x|^y, only defined for integer y
+        let one_subunits = PreciseDecimal::ONE.precise_subunits();
+        let y_integer = y.precise_subunits().checked_div(one_subunits)?;
+        if y_integer.checked_mul(one_subunits)? != y.precise_subunits() {
+            return None;
+        }
+    }
+    let exponent = x.checked_abs()?.ln()?.checked_mul(y)?;
+    let value = exponent.exp()?;
+    error_exp(exponent)?
+        .checked_add(value.checked_mul(ERROR_LN.checked_mul(y.checked_abs()?)?)?)?
+        .checked_add(ULP)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::power::PowerPreciseDecimal;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(ERROR_LN, pdec!("0.000000000000000002539783827598718036"));
+        assert_eq!(
+            ERROR_EXP_UNDERFLOW,
+            pdec!("0.000000000000000000000000000000000003")
+        );
+        assert_eq!(ULP, pdec!("0.000000000000000000000000000000000001"));
+    }
+
+    #[test_case(pdec!(0), pdec!("0.000000000000000001734723475976807095"); "zero")]
+    #[test_case(pdec!(1), pdec!("0.000000000000000003469446951953614189"); "one")]
+    #[test_case(pdec!(-1), pdec!("0.000000000000000000867361737988403548"); "minus one")]
+    #[test_case(pdec!(10), pdec!("0.000000000000028421709430404007434845"); "ten")]
+    #[test_case(pdec!(-10), pdec!("0.000000000000000000000105879118406788"); "minus ten")]
+    #[test_case(pdec!(42), pdec!(4); "forty two")]
+    #[test_case(pdec!(-42), pdec!("0.000000000000000000000000000000000001"); "minus forty two")]
+    #[test_case(pdec!(93), pdec!(37778931862957161709568); "max")]
+    #[test_case(pdec!(-82), pdec!("0.000000000000000000000000000000000001"); "min")]
+    #[test_case(pdec!(-83), pdec!("0.000000000000000000000000000000000003"); "underflow")]
+    fn test_error_exp(x: PreciseDecimal, expected: PreciseDecimal) {
+        assert_eq!(error_exp(x), Some(expected));
+    }
+
+    #[test]
+    fn test_error_exp_bound() {
+        let e = pdec!("2.718281828459045235360287471352662497");
+        let error = (pdec!(1).exp().unwrap() - e).checked_abs().unwrap();
+        assert!(error <= error_exp(pdec!(1)).unwrap());
+    }
+
+    #[test]
+    fn test_error_ln() {
+        assert_eq!(error_ln(), pdec!("0.000000000000000002539783827598718036"));
+        let ln2 = pdec!("0.693147180559945309417232121458176568");
+        let error = (pdec!(2).ln().unwrap() - ln2).checked_abs().unwrap();
+        assert!(error <= error_ln());
+    }
+
+    #[test_case(pdec!(2), pdec!(3), pdec!("0.000000000000000074832599670183689621"); "integer exponent")]
+    // ln(2) * 0.5 is truncated below ln2/2, therefore exp reduces its argument with k = 0 instead of 1
+    #[test_case(pdec!(2), pdec!("0.5"), pdec!("0.000000000000000003530621843219785951"); "square root")]
+    #[test_case(pdec!(10), pdec!(-2), pdec!("0.000000000000000000064348203708043167"); "negative exponent")]
+    #[test_case(pdec!("0.5"), pdec!(10), pdec!("0.000000000000000000026496642335902332"); "fraction")]
+    #[test_case(pdec!(-2), pdec!(3), pdec!("0.000000000000000074832599670183689621"); "negative base")]
+    fn test_error_pow(x: PreciseDecimal, y: PreciseDecimal, expected: PreciseDecimal) {
+        assert_eq!(error_pow(x, y), Some(expected));
+    }
+
+    #[test_case(pdec!(2), pdec!(3), pdec!(8); "integer exponent")]
+    #[test_case(pdec!(2), pdec!("0.5"), pdec!("1.414213562373095048801688724209698078"); "square root")]
+    #[test_case(pdec!(10), pdec!(-2), pdec!("0.01"); "negative exponent")]
+    #[test_case(pdec!(-2), pdec!(3), pdec!(-8); "negative base")]
+    fn test_error_pow_bound(x: PreciseDecimal, y: PreciseDecimal, expected: PreciseDecimal) {
+        let error = (x.pow(y).unwrap() - expected).checked_abs().unwrap();
+        assert!(error <= error_pow(x, y).unwrap());
+    }
+
+    #[test_case(pdec!(2), pdec!(0); "zero exponent")]
+    #[test_case(pdec!(2), pdec!(1); "one exponent")]
+    #[test_case(pdec!(1), pdec!(1000); "one base")]
+    #[test_case(pdec!(0), pdec!(2); "zero base")]
+    fn test_error_pow_exact(x: PreciseDecimal, y: PreciseDecimal) {
+        assert_eq!(error_pow(x, y), Some(PreciseDecimal::ZERO));
+    }
+
+    #[test_case(pdec!(0), pdec!(-2); "zero base negative exponent")]
+    #[test_case(pdec!(-2), pdec!("0.5"); "negative base fractional exponent")]
+    #[test_case(pdec!(10), pdec!(100); "overflow")]
+    fn test_error_pow_none(x: PreciseDecimal, y: PreciseDecimal) {
+        assert_eq!(error_pow(x, y), None);
+    }
+}
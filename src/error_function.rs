@@ -0,0 +1,365 @@
+/* origin: FreeBSD /usr/src/lib/msun/src/s_erf.c */
+/*
+ * ====================================================
+ * Copyright (C) 1993 by Sun Microsystems, Inc. All rights reserved.
+ *
+ * Developed at SunPro, a Sun Microsystems, Inc. business.
+ * Permission to use, copy, modify, and distribute this
+ * software is freely granted, provided that this notice
+ * is preserved.
+ * ====================================================
+ */
+/* erf(x), erfc(x)
+ * Return the error function and the complementary error function of x.
+ *
+ *                           x
+ *                    2      |\
+ *     erf(x)  =  ---------  | exp(-t*t)dt
+ *                 sqrt(pi) \|
+ *                           0
+ *
+ *     erfc(x) =  1-erf(x)
+ *  Note that
+ *              erf(-x) = -erf(x)
+ *              erfc(-x) = 2 - erfc(x)
+ *
+ * Method:
+ *      1. For |x| in [0, 0.84375]
+ *          erf(x)  = x + x*R(x^2)
+ *          erfc(x) = 1 - erf(x)           if x in [-.84375,0.25]
+ *                  = 0.5 + ((0.5-x)-x*R)  if x in [0.25,0.84375]
+ *         where R = P/Q where P is an odd poly of degree 8 and
+ *         Q is an odd poly of degree 10.
+ *                                               -57.90
+ *                      | R - (erf(x)-x)/x | <= 2
+ *
+ *      2. For |x| in [0.84375,1.25]
+ *         let s = |x| - 1, and c = 0.84506291151 rounded to single (24 bits)
+ *              erf(x)  = sign(x) * (c  + P1(s)/Q1(s))
+ *              erfc(x) = (1-c)  - P1(s)/Q1(s) if x > 0
+ *                        1+(c+P1(s)/Q1(s))    if x < 0
+ *                 |P1/Q1 - (erf(|x|)-c)| <= 2**-59.06
+ *
+ *      3. For x in [1.25,1/0.35(~2.857143)],
+ *              erfc(x) = (1/x)*exp(-x*x-0.5625+R1/S1)
+ *              erf(x)  = 1 - erfc(x)
+ *         where
+ *              R1(z) = degree 7 poly in z, (z=1/x^2)
+ *              S1(z) = degree 8 poly in z
+ *                 |R1/S1 - f(x)| < 2**(-62.57)
+ *
+ *      4. For x in [1/0.35,28]
+ *              erfc(x) = (1/x)*exp(-x*x-0.5625+R2/S2) if x > 0
+ *                      = 2.0 - (1/x)*exp(-x*x-0.5625+R2/S2) if -6<x<0
+ *                      = 2.0 - tiny            (if x <= -6)
+ *              erf(x)  = sign(x)*(1.0 - erfc(x)) if x < 6, else
+ *              erf(x)  = sign(x)*(1.0 - tiny)
+ *         where
+ *              R2(z) = degree 6 poly in z, (z=1/x^2)
+ *              S2(z) = degree 7 poly in z
+ *
+ *      5. For inf > x >= 28
+ *              erf(x)  = sign(x) *(1 - tiny)  (raise inexact)
+ *              erfc(x) = tiny*tiny (raise underflow) if x > 0
+ *                      = 2 - tiny if x<0
+ *
+ * Special cases:
+ *      erf(0)  = 0, erf(inf)  = 1, erf(-inf) = -1,
+ *      erfc(0) = 1, erfc(inf) = 0, erfc(-inf) = 2,
+ *      erfc/erf(NaN) is NaN
+ *
+ * Misc:
+ *      The tiny argument shortcuts for |x| < 2^-28 and the hi/lo split of x in the tail
+ *      (exp(-z*z-0.5625)*exp((z-x)*(z+x)+R/S)) are not needed for PreciseDecimal due to
+ *      exact integer calculation.
+ *      The cut-off at |x| >= 6 for erf does not apply because PreciseDecimal can still represent
+ *      erfc(x) there. Instead the tail is evaluated up to 28, where exp underflows to zero anyway.
+ */
+
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::polynomial::horner;
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, Decimal, PreciseDecimal,
+};
+use radix_common_derive::pdec;
+
+const HALF: PreciseDecimal = pdec!("0.5");
+const ERX: PreciseDecimal = pdec!("0.845062911510467529297"); // 8.45062911510467529297e-01
+const TAIL_CUTOFF: PreciseDecimal = pdec!(28);
+const ERX_CUTOFF: PreciseDecimal = pdec!("0.84375");
+const TAIL_START: PreciseDecimal = pdec!("1.25");
+const TAIL_SPLIT: PreciseDecimal = pdec!("2.857142857142857142857142857142857142"); // 1/0.35
+const TAIL_OFFSET: PreciseDecimal = pdec!("0.5625");
+
+// Coefficients for approximation to erf on [0,0.84375]
+const PP0: PreciseDecimal = pdec!("0.128379167095512558561"); // 1.28379167095512558561e-01
+const PP1: PreciseDecimal = pdec!("-0.325042107247001499370"); // -3.25042107247001499370e-01
+const PP2: PreciseDecimal = pdec!("-0.0284817495755985104766"); // -2.84817495755985104766e-02
+const PP3: PreciseDecimal = pdec!("-0.00577027029648944159157"); // -5.77027029648944159157e-03
+const PP4: PreciseDecimal = pdec!("-0.0000237630166566501626084"); // -2.37630166566501626084e-05
+const QQ1: PreciseDecimal = pdec!("0.397917223959155352819"); // 3.97917223959155352819e-01
+const QQ2: PreciseDecimal = pdec!("0.0650222499887672944485"); // 6.50222499887672944485e-02
+const QQ3: PreciseDecimal = pdec!("0.00508130628187576562776"); // 5.08130628187576562776e-03
+const QQ4: PreciseDecimal = pdec!("0.000132494738004321644526"); // 1.32494738004321644526e-04
+const QQ5: PreciseDecimal = pdec!("-0.00000396022827877536812320"); // -3.96022827877536812320e-06
+
+// Coefficients for approximation to erf in [0.84375,1.25]
+const PA0: PreciseDecimal = pdec!("-0.00236211856075265944077"); // -2.36211856075265944077e-03
+const PA1: PreciseDecimal = pdec!("0.414856118683748331666"); // 4.14856118683748331666e-01
+const PA2: PreciseDecimal = pdec!("-0.372207876035701323847"); // -3.72207876035701323847e-01
+const PA3: PreciseDecimal = pdec!("0.318346619901161753674"); // 3.18346619901161753674e-01
+const PA4: PreciseDecimal = pdec!("-0.110894694282396677476"); // -1.10894694282396677476e-01
+const PA5: PreciseDecimal = pdec!("0.0354783043256182359371"); // 3.54783043256182359371e-02
+const PA6: PreciseDecimal = pdec!("-0.00216637559486879084300"); // -2.16637559486879084300e-03
+const QA1: PreciseDecimal = pdec!("0.106420880400844228286"); // 1.06420880400844228286e-01
+const QA2: PreciseDecimal = pdec!("0.540397917702171048937"); // 5.40397917702171048937e-01
+const QA3: PreciseDecimal = pdec!("0.0718286544141962662868"); // 7.18286544141962662868e-02
+const QA4: PreciseDecimal = pdec!("0.126171219808761642112"); // 1.26171219808761642112e-01
+const QA5: PreciseDecimal = pdec!("0.0136370839120290507362"); // 1.36370839120290507362e-02
+const QA6: PreciseDecimal = pdec!("0.0119844998467991074170"); // 1.19844998467991074170e-02
+
+// Coefficients for approximation to erfc in [1.25,1/0.35]
+const RA0: PreciseDecimal = pdec!("-0.00986494403484714822705"); // -9.86494403484714822705e-03
+const RA1: PreciseDecimal = pdec!("-0.693858572707181764372"); // -6.93858572707181764372e-01
+const RA2: PreciseDecimal = pdec!("-10.5586262253232909814"); // -1.05586262253232909814e+01
+const RA3: PreciseDecimal = pdec!("-62.3753324503260060396"); // -6.23753324503260060396e+01
+const RA4: PreciseDecimal = pdec!("-162.396669462573470355"); // -1.62396669462573470355e+02
+const RA5: PreciseDecimal = pdec!("-184.605092906711035994"); // -1.84605092906711035994e+02
+const RA6: PreciseDecimal = pdec!("-81.2874355063065934246"); // -8.12874355063065934246e+01
+const RA7: PreciseDecimal = pdec!("-9.81432934416914548592"); // -9.81432934416914548592e+00
+const SA1: PreciseDecimal = pdec!("19.6512716674392571292"); // 1.96512716674392571292e+01
+const SA2: PreciseDecimal = pdec!("137.657754143519042600"); // 1.37657754143519042600e+02
+const SA3: PreciseDecimal = pdec!("434.565877475229228821"); // 4.34565877475229228821e+02
+const SA4: PreciseDecimal = pdec!("645.387271733267880336"); // 6.45387271733267880336e+02
+const SA5: PreciseDecimal = pdec!("429.008140027567833386"); // 4.29008140027567833386e+02
+const SA6: PreciseDecimal = pdec!("108.635005541779435134"); // 1.08635005541779435134e+02
+const SA7: PreciseDecimal = pdec!("6.57024977031928170135"); // 6.57024977031928170135e+00
+const SA8: PreciseDecimal = pdec!("-0.0604244152148580987438"); // -6.04244152148580987438e-02
+
+// Coefficients for approximation to erfc in [1/.35,28]
+const RB0: PreciseDecimal = pdec!("-0.00986494292470009928597"); // -9.86494292470009928597e-03
+const RB1: PreciseDecimal = pdec!("-0.799283237680523006574"); // -7.99283237680523006574e-01
+const RB2: PreciseDecimal = pdec!("-17.7579549177547519889"); // -1.77579549177547519889e+01
+const RB3: PreciseDecimal = pdec!("-160.636384855821916062"); // -1.60636384855821916062e+02
+const RB4: PreciseDecimal = pdec!("-637.566443368389627722"); // -6.37566443368389627722e+02
+const RB5: PreciseDecimal = pdec!("-1025.09513161107724954"); // -1.02509513161107724954e+03
+const RB6: PreciseDecimal = pdec!("-483.519191608651397019"); // -4.83519191608651397019e+02
+const SB1: PreciseDecimal = pdec!("30.3380607434824582924"); // 3.03380607434824582924e+01
+const SB2: PreciseDecimal = pdec!("325.792512996573918826"); // 3.25792512996573918826e+02
+const SB3: PreciseDecimal = pdec!("1536.72958608443695994"); // 1.53672958608443695994e+03
+const SB4: PreciseDecimal = pdec!("3199.85821950859553908"); // 3.19985821950859553908e+03
+const SB5: PreciseDecimal = pdec!("2553.05040643316442583"); // 2.55305040643316442583e+03
+const SB6: PreciseDecimal = pdec!("474.528541206955367215"); // 4.74528541206955367215e+02
+const SB7: PreciseDecimal = pdec!("-22.4409524465858183362"); // -2.24409524465858183362e+01
+
+pub trait ErrorFunctionDecimal {
+    fn erf(&self) -> Option<Decimal>;
+    fn erfc(&self) -> Option<Decimal>;
+}
+
+pub trait ErrorFunctionPreciseDecimal {
+    fn erf(&self) -> Option<PreciseDecimal>;
+    fn erfc(&self) -> Option<PreciseDecimal>;
+}
+
+/// Approximates (erf(x) - x) / x for |x| < 0.84375
+fn erf_small(x: PreciseDecimal) -> Option<PreciseDecimal> {
+    let z = x.checked_mul(x)?;
+    let r = horner(z, &[PP0, PP1, PP2, PP3, PP4])?;
+    let s = horner(z, &[PreciseDecimal::ONE, QQ1, QQ2, QQ3, QQ4, QQ5])?;
+    r.checked_div(s)
+}
+
+/// Approximates erf(|x|) - erx for 0.84375 <= |x| < 1.25
+fn erf_near_one(x: PreciseDecimal) -> Option<PreciseDecimal> {
+    let s = x.checked_sub(PreciseDecimal::ONE)?;
+    let p = horner(s, &[PA0, PA1, PA2, PA3, PA4, PA5, PA6])?;
+    let q = horner(s, &[PreciseDecimal::ONE, QA1, QA2, QA3, QA4, QA5, QA6])?;
+    p.checked_div(q)
+}
+
+/// Approximates ln(x * erfc(x)) = -x*x - 0.5625 + R/S for 1.25 <= x < 28
+pub(crate) fn erfc_tail_exponent(x: PreciseDecimal) -> Option<PreciseDecimal> {
+    let xx = x.checked_mul(x)?;
+    let s = PreciseDecimal::ONE.checked_div(xx)?;
+    let (r, ss) = if x < TAIL_SPLIT {
+        (
+            horner(s, &[RA0, RA1, RA2, RA3, RA4, RA5, RA6, RA7])?,
+            horner(
+                s,
+                &[PreciseDecimal::ONE, SA1, SA2, SA3, SA4, SA5, SA6, SA7, SA8],
+            )?,
+        )
+    } else {
+        (
+            horner(s, &[RB0, RB1, RB2, RB3, RB4, RB5, RB6])?,
+            horner(s, &[PreciseDecimal::ONE, SB1, SB2, SB3, SB4, SB5, SB6, SB7])?,
+        )
+    };
+    xx.checked_neg()?
+        .checked_sub(TAIL_OFFSET)?
+        .checked_add(r.checked_div(ss)?)
+}
+
+/// Approximates erfc(x) for 1.25 <= x < 28
+fn erfc_tail(x: PreciseDecimal) -> Option<PreciseDecimal> {
+    erfc_tail_exponent(x)?.exp()?.checked_div(x)
+}
+
+impl ErrorFunctionDecimal for Decimal {
+    /// Calculates the error function of a Decimal
+    /// Using the error function of PreciseDecimal internally
+    fn erf(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .erf()
+            .and_then(|erf| erf.try_into().ok())
+    }
+
+    /// Calculates the complementary error function of a Decimal
+    /// Using the complementary error function of PreciseDecimal internally
+    fn erfc(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .erfc()
+            .and_then(|erfc| erfc.try_into().ok())
+    }
+}
+
+impl ErrorFunctionPreciseDecimal for PreciseDecimal {
+    /// Calculates the error function of a PreciseDecimal
+    fn erf(&self) -> Option<PreciseDecimal> {
+        // based on https://github.com/rust-lang/libm/blob/master/src/math/erf.rs
+        let x = *self;
+        // (5) before |x|, which overflows for MIN
+        if x <= TAIL_CUTOFF.checked_neg()? {
+            return PreciseDecimal::ONE.checked_neg();
+        }
+        let abs = x.checked_abs()?;
+        if abs < ERX_CUTOFF {
+            // (1)
+            return x.checked_add(x.checked_mul(erf_small(x)?)?);
+        }
+        let erf_abs = if abs < TAIL_START {
+            // (2)
+            ERX.checked_add(erf_near_one(abs)?)?
+        } else if abs < TAIL_CUTOFF {
+            // (3) and (4)
+            PreciseDecimal::ONE.checked_sub(erfc_tail(abs)?)?
+        } else {
+            // (5)
+            PreciseDecimal::ONE
+        };
+        if x.is_negative() {
+            return erf_abs.checked_neg();
+        }
+        Some(erf_abs)
+    }
+
+    /// Calculates the complementary error function of a PreciseDecimal
+    fn erfc(&self) -> Option<PreciseDecimal> {
+        let x = *self;
+        // (5) before |x|, which overflows for MIN
+        if x <= TAIL_CUTOFF.checked_neg()? {
+            return Some(pdec!(2));
+        }
+        let abs = x.checked_abs()?;
+        if abs < ERX_CUTOFF {
+            // (1)
+            let r = x.checked_mul(erf_small(x)?)?;
+            if x < pdec!("0.25") {
+                return PreciseDecimal::ONE.checked_sub(x.checked_add(r)?);
+            }
+            return HALF.checked_sub(x.checked_sub(HALF)?.checked_add(r)?);
+        }
+        let erfc_abs = if abs < TAIL_START {
+            // (2)
+            PreciseDecimal::ONE
+                .checked_sub(ERX)?
+                .checked_sub(erf_near_one(abs)?)?
+        } else if abs < TAIL_CUTOFF {
+            // (3) and (4)
+            erfc_tail(abs)?
+        } else {
+            // (5)
+            PreciseDecimal::ZERO
+        };
+        if x.is_negative() {
+            return pdec!(2).checked_sub(erfc_abs);
+        }
+        Some(erfc_abs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common_derive::dec;
+    use test_case::test_case;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(ERX, pdec!("0.845062911510467529297"));
+        assert_eq!(TAIL_SPLIT, pdec!(1) / pdec!("0.35"));
+    }
+
+    #[test]
+    fn test_erf_zero() {
+        assert_eq!(pdec!(0).erf(), Some(pdec!(0)));
+        assert_eq!(pdec!(0).erfc(), Some(pdec!(1)));
+    }
+
+    #[test]
+    fn test_erf_decimal() {
+        assert_eq!(dec!(0).erf(), Some(dec!(0)));
+        assert_eq!(dec!(1).erf(), Some(dec!("0.842700792949714869")));
+        assert_eq!(dec!(-1).erf(), Some(dec!("-0.842700792949714869")));
+        assert_eq!(dec!(2).erfc(), Some(dec!("0.004677734981047265")));
+        assert_eq!(dec!(-2).erfc(), Some(dec!("1.995322265018952734")));
+        assert_eq!(dec!(10).erfc(), Some(dec!(0)));
+        assert_eq!(Decimal::MAX.erf(), Some(dec!(1)));
+        assert_eq!(Decimal::MIN.erf(), Some(dec!(-1)));
+        assert_eq!(Decimal::MIN.erfc(), Some(dec!(2)));
+    }
+
+    #[test]
+    fn test_erf_odd_symmetry() {
+        for x in [pdec!("0.3"), pdec!("1.1"), pdec!(2), pdec!(5)] {
+            assert_eq!((-x).erf(), x.erf().map(|erf| -erf));
+            assert_eq!(x.erf().unwrap() + x.erfc().unwrap(), pdec!(1));
+        }
+    }
+
+    #[test]
+    fn test_erf_extreme() {
+        assert_eq!(PreciseDecimal::MAX.erf(), Some(pdec!(1)));
+        assert_eq!(PreciseDecimal::MIN.erf(), Some(pdec!(-1)));
+        assert_eq!(PreciseDecimal::MIN.erfc(), Some(pdec!(2)));
+        assert_eq!(PreciseDecimal::MAX.erfc(), Some(pdec!(0)));
+        assert_eq!(Decimal::MIN.erf(), Some(dec!(-1)));
+        assert_eq!(pdec!(-28).erf(), Some(pdec!(-1)));
+        assert_eq!(pdec!(28).erfc(), Some(pdec!(0)));
+        assert_eq!(pdec!(-28).erfc(), Some(pdec!(2)));
+        assert_eq!(pdec!(10).erfc(), Some(pdec!(0)));
+    }
+
+    #[test_case(pdec!("0.1"), pdec!("0.112462916018284892203275071743968383"); "small interval")]
+    #[test_case(pdec!("-0.5"), pdec!("-0.520499877813046537682746653891964528"); "small interval negative")]
+    #[test_case(pdec!(1), pdec!("0.842700792949714869341220635082609259"); "interval near one")]
+    #[test_case(pdec!(2), pdec!("0.995322265018952734162069256367252928"); "first tail interval")]
+    #[test_case(pdec!(-4), pdec!("-0.999999984582742099719981147840326513"); "second tail interval negative")]
+    #[test_case(pdec!(7), pdec!("0.999999999999999999999958161743922205"); "beyond double cut-off")]
+    fn test_erf_error(x: PreciseDecimal, target_result: PreciseDecimal) {
+        let error = (x.erf().unwrap() - target_result).checked_abs().unwrap();
+        assert!(error <= pdec!("0.000000000000000006"));
+    }
+
+    #[test_case(pdec!("0.1"), pdec!("0.887537083981715107796724928256031616"); "small interval")]
+    #[test_case(pdec!("0.5"), pdec!("0.479500122186953462317253346108035471"); "small interval above quarter")]
+    #[test_case(pdec!(-1), pdec!("1.842700792949714869341220635082609259"); "interval near one negative")]
+    #[test_case(pdec!(2), pdec!("0.004677734981047265837930743632747071"); "first tail interval")]
+    #[test_case(pdec!(5), pdec!("0.000000000001537459794428034850188343"); "second tail interval")]
+    fn test_erfc_error(x: PreciseDecimal, target_result: PreciseDecimal) {
+        let error = (x.erfc().unwrap() - target_result).checked_abs().unwrap();
+        assert!(error <= pdec!("0.000000000000000006"));
+    }
+}
@@ -60,14 +60,18 @@
  *      1 ulp (unit in the last place).
  */
 
+use crate::polynomial::horner;
 use num_traits::ToPrimitive;
-use radix_common::math::{Decimal, PreciseDecimal};
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Decimal, PreciseDecimal, I256,
+};
 use radix_common_derive::{dec, pdec};
 
-const LN2: PreciseDecimal = pdec!("0.693147180559945309417232121458176568");
-const HALF_POSITIVE: PreciseDecimal = pdec!("0.5");
-const HALF_NEGATIVE: PreciseDecimal = pdec!("-0.5");
-const INVLN2: PreciseDecimal = pdec!("1.442695040888963407359924681001892137");
+pub(crate) const LN2: PreciseDecimal = pdec!("0.693147180559945309417232121458176568");
+pub(crate) const HALF_POSITIVE: PreciseDecimal = pdec!("0.5");
+pub(crate) const HALF_NEGATIVE: PreciseDecimal = pdec!("-0.5");
+pub(crate) const INVLN2: PreciseDecimal = pdec!("1.442695040888963407359924681001892137");
+const TWO: PreciseDecimal = pdec!(2);
 
 const P1: PreciseDecimal = pdec!("0.166666666666666019037"); // 1.66666666666666019037e-01
 const P2: PreciseDecimal = pdec!("-0.00277777777770155933842"); // -2.77777777770155933842e-03
@@ -93,8 +97,7 @@ impl ExponentialDecimal for Decimal {
         if self > &dec!(90) {
             return None;
         }
-        PreciseDecimal::try_from(*self)
-            .ok()?
+        PreciseDecimal::from(*self)
             .exp()
             .and_then(|e| e.try_into().ok())
     }
@@ -125,23 +128,26 @@ impl ExponentialPreciseDecimal for PreciseDecimal {
 
         // r = x - floor(x/ln(2) +- 0.5) * ln(2)
         // https://www.wolframalpha.com/input?i=x+-+floor%28x%2Fln%282%29+%2B+0.5%29+*+ln%282%29
-        let k = INVLN2 * *self + signed_half;
-        let k: i32 = (k.precise_subunits() / one_subunits).to_i32().unwrap();
-        let r = *self - LN2 * k;
+        let k = INVLN2.checked_mul(*self)?.checked_add(signed_half)?;
+        let k: i32 = k.precise_subunits().checked_div(one_subunits)?.to_i32()?;
+        let r = self.checked_sub(LN2.checked_mul(k)?)?;
 
         // (2) Approximation of exp(r)
-        let rr = r * r;
-        let c = r - rr * (P1 + rr * (P2 + rr * (P3 + rr * (P4 + rr * P5))));
-        let exp_r = PreciseDecimal::ONE + r + (r * c) / (dec!(2) - c);
+        let rr = r.checked_mul(r)?;
+        let p = horner(rr, &[P1, P2, P3, P4, P5])?;
+        let c = r.checked_sub(rr.checked_mul(p)?)?;
+        let exp_r = PreciseDecimal::ONE
+            .checked_add(r)?
+            .checked_add(r.checked_mul(c)?.checked_div(TWO.checked_sub(c)?)?)?;
 
         // (3) Scale back
         let two_pow_k_subunits = if self.is_negative() {
-            one_subunits >> k.abs() as u32
+            I256(one_subunits.0.checked_shr(k.unsigned_abs())?)
         } else {
-            one_subunits << k as u32 // k <= 130
+            I256(one_subunits.0.checked_shl(k.unsigned_abs())?) // k <= 130
         };
         let two_pow_k = PreciseDecimal::from_precise_subunits(two_pow_k_subunits);
-        Some(two_pow_k * exp_r)
+        two_pow_k.checked_mul(exp_r)
     }
 }
 
@@ -157,6 +163,7 @@ mod tests {
         assert_eq!(HALF_POSITIVE, pdec!("0.5"));
         assert_eq!(HALF_NEGATIVE, pdec!("-0.5"));
         assert_eq!(INVLN2, pdec!("1.442695040888963407359924681001892137"));
+        assert_eq!(TWO, pdec!(2));
         assert_eq!(P1, pdec!("0.166666666666666019037"));
         assert_eq!(P2, pdec!("-0.00277777777770155933842"));
         assert_eq!(P3, pdec!("0.0000661375632143793436117"));
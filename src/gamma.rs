@@ -0,0 +1,352 @@
+// Gamma, log-gamma and Beta functions
+//
+// Method:
+//      1. Argument shift: for x < 15 use the recurrence Γ(x+1) = x*Γ(x) to obtain y = x + n >= 15
+//              Γ(x) = Γ(y) / (x*(x+1)*...*(x+n-1))
+//         For x below about -26 the product exceeds PreciseDecimal, so whenever the next factor would overflow the
+//         product is folded into a sum of logarithms: ln|x*(x+1)*...*(x+n-1)| = ln|p_1| + ln|p_2| + ...
+//         with p_i the partial products between two folds.
+//
+//      2. Stirling series for y >= 15:
+//              lnΓ(y) = (y - 1/2)*ln(y) - y + ln(2π)/2 + Σ B_2k / (2k*(2k-1)*y^(2k-1))
+//         with the Bernoulli numbers B_2k. Nine terms bound the truncation error of the series by
+//              |B_20 / (20*19*y^19)| <= 1.4 / 15^19 ~ 6.3*10^-23
+//
+//      3. Γ(x) = ±e^(lnΓ(y) - ln|x*(x+1)*...*(x+n-1)|)
+//         B(a, b) = e^(lnΓ(a) + lnΓ(b) - lnΓ(a+b))
+//
+// Special cases:
+//      1. Γ(n) = (n-1)! is calculated exactly for positive integers n <= 36.
+//      2. Γ(x) has poles at 0, -1, -2, ..., returning None.
+//      3. Γ(x) underflows to zero for x < -60: near the pole -n the gamma function behaves like
+//              |Γ(-n + ε)| ~ 1 / (n! * |ε|)
+//         and |ε| >= 10^-36 for any PreciseDecimal, so |Γ(x)| <= 10^36 / 60! < 10^-45.
+//      4. lnΓ(x) and B(a, b) are only defined for positive arguments.
+//
+// Accuracy:
+//      The error of lnΓ(y) is dominated by the error of ln scaled by (y - 1/2):
+//              error_ln_gamma(y) ~ (y - 1/2) * 2^-58.45
+//      Γ(x) has a relative error of the same order due to e^(a + error) ~ e^a * (1 + error).
+
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::logarithm::LogarithmPreciseDecimal;
+use crate::polynomial::horner;
+use num_traits::ToPrimitive;
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, Decimal, PreciseDecimal, I256,
+};
+use radix_common_derive::pdec;
+
+const HALF: PreciseDecimal = pdec!("0.5");
+const HALF_LN_2PI: PreciseDecimal = pdec!("0.918938533204672741780329736405617639");
+const STIRLING_MIN: PreciseDecimal = pdec!(15);
+const GAMMA_INTEGER_MAX: i32 = 36; // Γ(36) = 35! is the largest factorial representable as PreciseDecimal
+const UNDERFLOW: PreciseDecimal = pdec!(-60);
+
+// Coefficients B_2k / (2k*(2k-1)) of the Stirling series
+const S1: PreciseDecimal = pdec!("0.083333333333333333333333333333333333"); // 1/12
+const S2: PreciseDecimal = pdec!("-0.002777777777777777777777777777777777"); // -1/360
+const S3: PreciseDecimal = pdec!("0.000793650793650793650793650793650793"); // 1/1260
+const S4: PreciseDecimal = pdec!("-0.000595238095238095238095238095238095"); // -1/1680
+const S5: PreciseDecimal = pdec!("0.000841750841750841750841750841750841"); // 1/1188
+const S6: PreciseDecimal = pdec!("-0.001917526917526917526917526917526917"); // -691/360360
+const S7: PreciseDecimal = pdec!("0.006410256410256410256410256410256410"); // 1/156
+const S8: PreciseDecimal = pdec!("-0.029550653594771241830065359477124183"); // -3617/122400
+const S9: PreciseDecimal = pdec!("0.179644372368830573164938490015889396"); // 43867/244188
+
+pub trait GammaDecimal {
+    fn gamma(&self) -> Option<Decimal>;
+    fn ln_gamma(&self) -> Option<Decimal>;
+    fn beta(&self, other: Decimal) -> Option<Decimal>;
+}
+
+pub trait GammaPreciseDecimal {
+    fn gamma(&self) -> Option<PreciseDecimal>;
+    fn ln_gamma(&self) -> Option<PreciseDecimal>;
+    fn beta(&self, other: PreciseDecimal) -> Option<PreciseDecimal>;
+}
+
+/// Returns the integer value if the number has no fractional part
+fn to_integer(number: PreciseDecimal) -> Option<I256> {
+    let one_subunits = PreciseDecimal::ONE.precise_subunits();
+    let integer = number.precise_subunits().checked_div(one_subunits)?;
+    if integer.checked_mul(one_subunits)? != number.precise_subunits() {
+        return None;
+    }
+    Some(integer)
+}
+
+/// Calculates Γ(n) = (n-1)! exactly
+fn gamma_integer(n: i32) -> Option<PreciseDecimal> {
+    (2..n).try_fold(PreciseDecimal::ONE, |product, i| product.checked_mul(i))
+}
+
+/// Shifts x to y = x + n >= 15 returning y, ln|x*(x+1)*...*(x+n-1)| and whether the product is negative
+fn shift_argument(x: PreciseDecimal) -> Option<(PreciseDecimal, PreciseDecimal, bool)> {
+    let mut y = x;
+    let mut product = PreciseDecimal::ONE;
+    let mut ln_product = PreciseDecimal::ZERO;
+    while y < STIRLING_MIN {
+        product = match product.checked_mul(y) {
+            Some(product) => product,
+            None => {
+                // fold the partial product into the sum of logarithms before it overflows
+                ln_product = ln_product.checked_add(product.checked_abs()?.ln()?)?;
+                if product.is_negative() {
+                    y.checked_neg()?
+                } else {
+                    y
+                }
+            }
+        };
+        y = y.checked_add(PreciseDecimal::ONE)?;
+    }
+    let ln_product = ln_product.checked_add(product.checked_abs()?.ln()?)?;
+    Some((y, ln_product, product.is_negative()))
+}
+
+/// Calculates lnΓ(y) for y >= 15 with the Stirling series
+fn ln_gamma_stirling(y: PreciseDecimal) -> Option<PreciseDecimal> {
+    let s = PreciseDecimal::ONE.checked_div(y)?;
+    let ss = s.checked_mul(s)?;
+    let series = s.checked_mul(horner(ss, &[S1, S2, S3, S4, S5, S6, S7, S8, S9])?)?;
+    y.checked_sub(HALF)?
+        .checked_mul(y.ln()?)?
+        .checked_sub(y)?
+        .checked_add(HALF_LN_2PI)?
+        .checked_add(series)
+}
+
+impl GammaDecimal for Decimal {
+    /// Calculates the gamma function of a Decimal
+    /// Using the gamma function of PreciseDecimal internally
+    fn gamma(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .gamma()
+            .and_then(|gamma| gamma.try_into().ok())
+    }
+
+    /// Calculates the natural logarithm of the gamma function of a Decimal
+    /// Using the natural logarithm of the gamma function of PreciseDecimal internally
+    fn ln_gamma(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .ln_gamma()
+            .and_then(|ln_gamma| ln_gamma.try_into().ok())
+    }
+
+    /// Calculates the beta function of two Decimals
+    /// Using the beta function of PreciseDecimal internally
+    fn beta(&self, other: Decimal) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .beta(PreciseDecimal::from(other))
+            .and_then(|beta| beta.try_into().ok())
+    }
+}
+
+impl GammaPreciseDecimal for PreciseDecimal {
+    /// Calculates the gamma function of a PreciseDecimal
+    fn gamma(&self) -> Option<PreciseDecimal> {
+        if let Some(n) = to_integer(*self) {
+            if n <= I256::ZERO {
+                // special case (2)
+                return None;
+            }
+            if n <= I256::from(GAMMA_INTEGER_MAX) {
+                // special case (1)
+                return gamma_integer(n.to_i32()?);
+            }
+        }
+        if *self < UNDERFLOW {
+            // special case (3)
+            return Some(PreciseDecimal::ZERO);
+        }
+
+        // (1) Argument shift
+        let (y, ln_product, negative) = shift_argument(*self)?;
+
+        // (2) Stirling series and (3) scale back
+        let gamma = ln_gamma_stirling(y)?.checked_sub(ln_product)?.exp()?;
+        if negative {
+            return gamma.checked_neg();
+        }
+        Some(gamma)
+    }
+
+    /// Calculates the natural logarithm of the gamma function of a PreciseDecimal
+    fn ln_gamma(&self) -> Option<PreciseDecimal> {
+        if !self.is_positive() {
+            // special case (4)
+            return None;
+        }
+        if *self == PreciseDecimal::ONE || *self == pdec!(2) {
+            return Some(PreciseDecimal::ZERO);
+        }
+
+        // (1) Argument shift
+        let (y, ln_product, _) = shift_argument(*self)?;
+
+        // (2) Stirling series and (3) scale back
+        ln_gamma_stirling(y)?.checked_sub(ln_product)
+    }
+
+    /// Calculates the beta function B(self, other) of two PreciseDecimals
+    fn beta(&self, other: PreciseDecimal) -> Option<PreciseDecimal> {
+        if !self.is_positive() || !other.is_positive() {
+            // special case (4)
+            return None;
+        }
+        let sum = self.checked_add(other)?;
+        if let (Some(a), Some(b)) = (to_integer(*self), to_integer(other)) {
+            let n = a.checked_add(b)?;
+            if n <= I256::from(GAMMA_INTEGER_MAX) {
+                // special case (1)
+                return gamma_integer(a.to_i32()?)?
+                    .checked_mul(gamma_integer(b.to_i32()?)?)?
+                    .checked_div(gamma_integer(n.to_i32()?)?);
+            }
+        }
+        self.ln_gamma()?
+            .checked_add(other.ln_gamma()?)?
+            .checked_sub(sum.ln_gamma()?)?
+            .exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common_derive::dec;
+    use test_case::test_case;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(HALF, pdec!("0.5"));
+        assert_eq!(HALF_LN_2PI, pdec!("0.918938533204672741780329736405617639"));
+        assert_eq!(S1, pdec!(1) / pdec!(12));
+        assert_eq!(S2, pdec!(-1) / pdec!(360));
+        assert_eq!(S3, pdec!(1) / pdec!(1260));
+        assert_eq!(S4, pdec!(-1) / pdec!(1680));
+        assert_eq!(S5, pdec!(1) / pdec!(1188));
+        assert_eq!(S6, pdec!(-691) / pdec!(360360));
+        assert_eq!(S7, pdec!(1) / pdec!(156));
+        assert_eq!(S8, pdec!(-3617) / pdec!(122400));
+        assert_eq!(S9, pdec!(43867) / pdec!(244188));
+    }
+
+    #[test]
+    fn test_gamma_factorial() {
+        assert_eq!(dec!(1).gamma(), Some(dec!(1)));
+        assert_eq!(dec!(2).gamma(), Some(dec!(1)));
+        assert_eq!(dec!(3).gamma(), Some(dec!(2)));
+        assert_eq!(dec!(6).gamma(), Some(dec!(120)));
+        assert_eq!(dec!(11).gamma(), Some(dec!(3628800)));
+        assert_eq!(
+            dec!(31).gamma(),
+            Some(dec!("265252859812191058636308480000000"))
+        );
+        assert_eq!(
+            pdec!(36).gamma(),
+            Some(pdec!("10333147966386144929666651337523200000000"))
+        );
+    }
+
+    #[test]
+    fn test_gamma_overflow() {
+        assert_eq!(dec!(36).gamma(), None);
+        assert_eq!(pdec!(37).gamma(), None);
+        assert_eq!(pdec!("36.5").gamma(), None);
+        assert_eq!(PreciseDecimal::MAX.gamma(), None);
+    }
+
+    #[test]
+    fn test_gamma_poles() {
+        assert_eq!(dec!(0).gamma(), None);
+        assert_eq!(dec!(-1).gamma(), None);
+        assert_eq!(dec!(-42).gamma(), None);
+        assert_eq!(dec!(-10000000000).gamma(), None);
+        assert_eq!(
+            pdec!("-100000000000000000000000000000000000000").gamma(),
+            None
+        );
+        assert_eq!(PreciseDecimal::MIN.gamma(), Some(pdec!(0)));
+    }
+
+    #[test_case(pdec!("0.5"), pdec!("1.772453850905516027298167483341145182"), pdec!("0.00000000000000002"); "half")]
+    #[test_case(pdec!("0.000000000000000001"), pdec!("999999999999999999.422784335098467140"), pdec!(2); "tiny")]
+    #[test_case(pdec!("1.5"), pdec!("0.886226925452758013649083741670572591"), pdec!("0.00000000000000001"); "one and a half")]
+    #[test_case(pdec!("4.2"), pdec!("7.756689535793177638694759583009895225"), pdec!("0.0000000000000002"); "non integer")]
+    #[test_case(pdec!("25.3"), pdec!("1622777117670872872553379.373673719069413878"), pdec!(20000000); "large non integer")]
+    #[test_case(pdec!("-0.5"), pdec!("-3.544907701811032054596334966682290365"), pdec!("0.00000000000000005"); "negative half")]
+    #[test_case(pdec!("-2.5"), pdec!("-0.945308720482941881225689324448610764"), pdec!("0.00000000000000002"); "negative two and a half")]
+    #[test_case(pdec!("-3.5"), pdec!("0.270088205852269108921625521271031646"), pdec!("0.00000000000000001"); "negative three and a half")]
+    #[test_case(pdec!("-26.5"), pdec!("-0.000000000000000000000000001506119639"), pdec!("0.000000000000000000000000000000000001"); "negative twenty six and a half")]
+    #[test_case(pdec!("-30.5"), pdec!("-0.000000000000000000000000000000002135"), pdec!("0.000000000000000000000000000000000001"); "negative thirty and a half")]
+    #[test_case(pdec!("-40.000000000000000001"), pdec!("-0.000000000000000000000000000001225617"), pdec!("0.000000000000000000000000000000000001"); "close to pole")]
+    #[test_case(pdec!("-40.5"), pdec!(0), pdec!("0.000000000000000000000000000000000001"); "negative forty and a half")]
+    #[test_case(pdec!("-59.5"), pdec!(0), pdec!("0.000000000000000000000000000000000001"); "negative fifty nine and a half")]
+    fn test_gamma_error(
+        x: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (x.gamma().unwrap() - target_result).checked_abs().unwrap();
+        assert!(error <= max_error);
+    }
+
+    #[test]
+    fn test_ln_gamma_invalid() {
+        assert_eq!(dec!(0).ln_gamma(), None);
+        assert_eq!(dec!(-1).ln_gamma(), None);
+        assert_eq!(dec!("-0.5").ln_gamma(), None);
+    }
+
+    #[test]
+    fn test_ln_gamma_one_two() {
+        assert_eq!(dec!(1).ln_gamma(), Some(dec!(0)));
+        assert_eq!(dec!(2).ln_gamma(), Some(dec!(0)));
+    }
+
+    #[test_case(pdec!("0.5"), pdec!("0.572364942924700087071713675676529355"), pdec!("0.00000000000000005"); "half")]
+    #[test_case(pdec!(3), pdec!("0.693147180559945309417232121458176568"), pdec!("0.00000000000000005"); "three")]
+    #[test_case(pdec!("4.2"), pdec!("2.048555636960589809021368583200089479"), pdec!("0.00000000000000005"); "non integer")]
+    #[test_case(pdec!(100), pdec!("359.134205369575398776044010460286909612"), pdec!("0.0000000000000003"); "hundred")]
+    #[test_case(pdec!(1000000), pdec!("12815504.569147611659976971785017113153687975"), pdec!("0.000000000003"); "million")]
+    fn test_ln_gamma_error(
+        x: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (x.ln_gamma().unwrap() - target_result)
+            .checked_abs()
+            .unwrap();
+        assert!(error <= max_error);
+    }
+
+    #[test]
+    fn test_beta_integer() {
+        assert_eq!(dec!(1).beta(dec!(1)), Some(dec!(1)));
+        assert_eq!(dec!(2).beta(dec!(3)), Some(dec!("0.083333333333333333")));
+        assert_eq!(dec!(3).beta(dec!(2)), dec!(2).beta(dec!(3)));
+        assert_eq!(dec!(5).beta(dec!(5)), Some(dec!("0.001587301587301587")));
+    }
+
+    #[test]
+    fn test_beta_invalid() {
+        assert_eq!(dec!(0).beta(dec!(1)), None);
+        assert_eq!(dec!(1).beta(dec!(-1)), None);
+    }
+
+    #[test_case(pdec!("0.5"), pdec!("0.5"), pdec!("3.141592653589793238462643383279502884"), pdec!("0.0000000000000002"); "pi")]
+    #[test_case(pdec!("2.5"), pdec!("1.5"), pdec!("0.196349540849362077403915211454968930"), pdec!("0.00000000000000002"); "half integers")]
+    #[test_case(pdec!(20), pdec!(30), pdec!("0.000000000000001768188547306202686616"), pdec!("0.0000000000000000000000000000001"); "large integers")]
+    fn test_beta_error(
+        a: PreciseDecimal,
+        b: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (a.beta(b).unwrap() - target_result).checked_abs().unwrap();
+        assert!(error <= max_error);
+    }
+}
@@ -0,0 +1,358 @@
+// Numerical integration and compensated summation
+//
+// Method:
+//      1. Composite Simpson rule with an even number n of intervals of width h = (b-a)/n:
+//              ∫_a^b f(x) dx ~ h/3 * (f(x_0) + 4*f(x_1) + 2*f(x_2) + ... + 4*f(x_n-1) + f(x_n))
+//
+//      2. Composite 5-point Gauss-Legendre rule on n intervals with midpoints m_j and half width h:
+//              ∫_a^b f(x) dx ~ h * Σ_j Σ_k w_k * f(m_j + h*x_k)
+//         with the nodes x_k and weights w_k of the Legendre polynomial P_5, exact for polynomials
+//         up to degree 9.
+//
+//      3. Compensated summation: additions of PreciseDecimal are exact in fixed point arithmetic, only
+//         products are truncated to 36 decimals. Similar to Kahan/Neumaier summation the truncated
+//         remainders of the products (< 10^-36 each) are accumulated separately and added back once:
+//              Σ a_i*b_i = Σ trunc(a_i*b_i) + trunc(Σ remainder_i)
+//         which bounds the rounding error of the whole sum by 10^-36 instead of n * 10^-36.
+//
+// Cost:
+//      Simpson evaluates f exactly n+1 times, Gauss-Legendre exactly 5*n times.
+//
+// Accuracy:
+//      Truncation error of the rules for a sufficiently smooth f, with h the interval width:
+//              Simpson:          (b-a) * h^4 / 180 * max|f^(4)|
+//              Gauss-Legendre:   (b-a) * h^10 * 3.95*10^-13 * max|f^(10)|
+//      An absolute error ε of f adds (b-a) * ε, e.g. (b-a) * 2^-59 * max|f| for f = e^x.
+
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, PreciseDecimal, I256, I384,
+};
+use radix_common_derive::pdec;
+
+// Nodes and weights of the 5-point Gauss-Legendre rule on [-1, 1]
+const GL_X1: PreciseDecimal = pdec!("0.538469310105683091036314420700208804"); // sqrt(5 - 2*sqrt(10/7)) / 3
+const GL_X2: PreciseDecimal = pdec!("0.906179845938663992797626878299392965"); // sqrt(5 + 2*sqrt(10/7)) / 3
+const GL_W0: PreciseDecimal = pdec!("0.568888888888888888888888888888888888"); // 128/225
+const GL_W1: PreciseDecimal = pdec!("0.478628670499366468041291514835638192"); // (322 + 13*sqrt(70)) / 900
+const GL_W2: PreciseDecimal = pdec!("0.236926885056189087514264040719917362"); // (322 - 13*sqrt(70)) / 900
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegrationError {
+    /// Zero intervals, or an odd number of intervals for the Simpson rule
+    InvalidIntervals,
+    /// The integrand returned None
+    EvaluationFailed,
+    /// An intermediate result was not representable
+    Overflow,
+}
+
+/// Sum of PreciseDecimals and products of PreciseDecimals with compensated truncation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompensatedSum {
+    sum: PreciseDecimal,
+    /// Accumulated truncated remainders in units of 10^-72, always less than 10^-36 in magnitude
+    compensation: I384,
+}
+
+impl CompensatedSum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value`, which is exact
+    pub fn add(&mut self, value: PreciseDecimal) -> Option<()> {
+        self.sum = self.sum.checked_add(value)?;
+        Some(())
+    }
+
+    /// Adds the product `a * b` keeping its truncated remainder
+    pub fn add_product(&mut self, a: PreciseDecimal, b: PreciseDecimal) -> Option<()> {
+        let one = I384::from(PreciseDecimal::ONE.precise_subunits());
+        let product =
+            I384::from(a.precise_subunits()).checked_mul(I384::from(b.precise_subunits()))?;
+        let (product_carry, product_remainder) = div_rem(product, one)?;
+        let compensation = self.compensation.checked_add(product_remainder)?;
+        let (compensation_carry, compensation) = div_rem(compensation, one)?;
+        let carry = I256::try_from(product_carry.checked_add(compensation_carry)?).ok()?;
+        self.sum = self
+            .sum
+            .checked_add(PreciseDecimal::from_precise_subunits(carry))?;
+        self.compensation = compensation;
+        Some(())
+    }
+
+    /// Compensated sum, truncated towards zero like a single PreciseDecimal operation
+    // moving one ulp towards zero cannot overflow
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn value(&self) -> PreciseDecimal {
+        let ulp = PreciseDecimal::from_precise_subunits(I256::ONE);
+        if self.sum.is_positive() && self.compensation < I384::ZERO {
+            return self.sum - ulp;
+        }
+        if self.sum.is_negative() && self.compensation > I384::ZERO {
+            return self.sum + ulp;
+        }
+        self.sum
+    }
+}
+
+/// Truncated quotient and remainder of `value / divisor`
+fn div_rem(value: I384, divisor: I384) -> Option<(I384, I384)> {
+    let quotient = value.checked_div(divisor)?;
+    let remainder = value.checked_sub(quotient.checked_mul(divisor)?)?;
+    Some((quotient, remainder))
+}
+
+/// Calculates Σ values_i * weights_i with a single truncation
+///
+/// Returns None if the slices differ in length or the result is not representable.
+pub fn compensated_dot(
+    values: &[PreciseDecimal],
+    weights: &[PreciseDecimal],
+) -> Option<PreciseDecimal> {
+    if values.len() != weights.len() {
+        return None;
+    }
+    let mut sum = CompensatedSum::new();
+    for (value, weight) in values.iter().zip(weights) {
+        sum.add_product(*value, *weight)?;
+    }
+    Some(sum.value())
+}
+
+/// Calculates lower + (upper - lower) * numerator / denominator
+fn interpolate(
+    lower: PreciseDecimal,
+    width: PreciseDecimal,
+    numerator: u64,
+    denominator: u64,
+) -> Option<PreciseDecimal> {
+    lower.checked_add(
+        width
+            .checked_mul(PreciseDecimal::from(numerator))?
+            .checked_div(PreciseDecimal::from(denominator))?,
+    )
+}
+
+/// Integrates `f` over [`lower`, `upper`] with the composite Simpson rule on `intervals` intervals
+pub fn simpson(
+    f: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    lower: PreciseDecimal,
+    upper: PreciseDecimal,
+    intervals: u32,
+) -> Result<PreciseDecimal, IntegrationError> {
+    if intervals == 0 || intervals % 2 != 0 {
+        return Err(IntegrationError::InvalidIntervals);
+    }
+    let width = upper.checked_sub(lower).ok_or(IntegrationError::Overflow)?;
+    let mut sum = CompensatedSum::new();
+    for i in 0..=intervals {
+        let x = interpolate(lower, width, i.into(), intervals.into())
+            .ok_or(IntegrationError::Overflow)?;
+        let fx = f(x).ok_or(IntegrationError::EvaluationFailed)?;
+        let weight = if i == 0 || i == intervals {
+            PreciseDecimal::ONE
+        } else if i % 2 == 1 {
+            pdec!(4)
+        } else {
+            pdec!(2)
+        };
+        sum.add_product(weight, fx)
+            .ok_or(IntegrationError::Overflow)?;
+    }
+    // h/3 * Σ = Σ * (b-a) / (3n)
+    sum.value()
+        .checked_mul(width)
+        .and_then(|v| v.checked_div(PreciseDecimal::from(intervals).checked_mul(pdec!(3))?))
+        .ok_or(IntegrationError::Overflow)
+}
+
+/// Integrates `f` over [`lower`, `upper`] with the composite 5-point Gauss-Legendre rule on `intervals`
+/// intervals
+pub fn gauss_legendre(
+    f: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    lower: PreciseDecimal,
+    upper: PreciseDecimal,
+    intervals: u32,
+) -> Result<PreciseDecimal, IntegrationError> {
+    if intervals == 0 {
+        return Err(IntegrationError::InvalidIntervals);
+    }
+    let width = upper.checked_sub(lower).ok_or(IntegrationError::Overflow)?;
+    let half = PreciseDecimal::from(intervals)
+        .checked_mul(pdec!(2))
+        .and_then(|n| width.checked_div(n))
+        .ok_or(IntegrationError::Overflow)?;
+    let nodes = [
+        (PreciseDecimal::ZERO, GL_W0),
+        (GL_X1, GL_W1),
+        (-GL_X1, GL_W1),
+        (GL_X2, GL_W2),
+        (-GL_X2, GL_W2),
+    ];
+    let mut sum = CompensatedSum::new();
+    for j in 0..intervals {
+        // u64 holds 2 * u32::MAX + 1
+        let numerator = u64::from(j).saturating_mul(2).saturating_add(1);
+        let denominator = u64::from(intervals).saturating_mul(2);
+        let mid =
+            interpolate(lower, width, numerator, denominator).ok_or(IntegrationError::Overflow)?;
+        for (node, weight) in nodes {
+            let x = half
+                .checked_mul(node)
+                .and_then(|offset| mid.checked_add(offset))
+                .ok_or(IntegrationError::Overflow)?;
+            let fx = f(x).ok_or(IntegrationError::EvaluationFailed)?;
+            sum.add_product(weight, fx)
+                .ok_or(IntegrationError::Overflow)?;
+        }
+    }
+    sum.value()
+        .checked_mul(half)
+        .ok_or(IntegrationError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_estimation::ULP;
+    use crate::exponential::ExponentialPreciseDecimal;
+    use crate::logarithm::LogarithmPreciseDecimal;
+    use crate::power::PowerPreciseDecimal;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    fn assert_close(result: PreciseDecimal, target: PreciseDecimal, max_error: PreciseDecimal) {
+        let error = (result - target).checked_abs().unwrap();
+        assert!(error <= max_error, "{result} != {target}");
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(GL_W0, pdec!(128) / pdec!(225));
+        // the weights integrate a constant exactly
+        assert_close(
+            GL_W0 + GL_W1 * pdec!(2) + GL_W2 * pdec!(2),
+            pdec!(2),
+            ULP * pdec!(5),
+        );
+        // the nodes are roots of P_5(x) = (63x^5 - 70x^3 + 15x) / 8
+        for x in [GL_X1, GL_X2] {
+            let xx = x * x;
+            let p5 = x * (pdec!(63) * xx * xx - pdec!(70) * xx + pdec!(15)) / pdec!(8);
+            assert_close(p5, pdec!(0), ULP * pdec!(100));
+        }
+    }
+
+    #[test]
+    fn test_compensated_sum() {
+        let half = pdec!("0.5");
+        let mut sum = CompensatedSum::new();
+        for _ in 0..10 {
+            sum.add_product(ULP, half).unwrap();
+        }
+        // every truncated product is zero, the remainders add up to 5 ULP
+        assert_eq!(sum.value(), ULP * pdec!(5));
+        sum.add(pdec!(1)).unwrap();
+        assert_eq!(sum.value(), pdec!(1) + ULP * pdec!(5));
+    }
+
+    #[test]
+    fn test_compensated_sum_negative() {
+        let mut sum = CompensatedSum::new();
+        for _ in 0..10 {
+            sum.add_product(-ULP, pdec!("0.3")).unwrap();
+        }
+        assert_eq!(sum.value(), ULP * pdec!(-3));
+        sum.add_product(ULP, pdec!("0.8")).unwrap();
+        assert_eq!(sum.value(), ULP * pdec!(-2));
+    }
+
+    #[test]
+    fn test_compensated_dot() {
+        let third = pdec!(1) / pdec!(3);
+        let values = [third; 9];
+        let weights = [pdec!("0.1"); 9];
+        // the truncated products add up to 3 ULP below 0.3, the exact sum is only 0.3 ULP below
+        let plain = values.iter().fold(PreciseDecimal::ZERO, |sum, value| {
+            sum + *value * pdec!("0.1")
+        });
+        assert_eq!(plain, pdec!("0.3") - ULP * pdec!(3));
+        assert_eq!(compensated_dot(&values, &weights), Some(pdec!("0.3") - ULP));
+        assert_eq!(compensated_dot(&values, &weights[1..]), None);
+        assert_eq!(compensated_dot(&[], &[]), Some(pdec!(0)));
+    }
+
+    #[test]
+    fn test_invalid_intervals() {
+        let f = |x: PreciseDecimal| Some(x);
+        assert_eq!(
+            simpson(f, pdec!(0), pdec!(1), 0),
+            Err(IntegrationError::InvalidIntervals)
+        );
+        assert_eq!(
+            simpson(f, pdec!(0), pdec!(1), 3),
+            Err(IntegrationError::InvalidIntervals)
+        );
+        assert_eq!(
+            gauss_legendre(f, pdec!(0), pdec!(1), 0),
+            Err(IntegrationError::InvalidIntervals)
+        );
+    }
+
+    #[test]
+    fn test_evaluation_failed() {
+        let f = |x: PreciseDecimal| x.ln();
+        assert_eq!(
+            simpson(f, pdec!(0), pdec!(1), 2),
+            Err(IntegrationError::EvaluationFailed)
+        );
+        assert_eq!(
+            gauss_legendre(f, pdec!(-1), pdec!(1), 1),
+            Err(IntegrationError::EvaluationFailed)
+        );
+    }
+
+    #[test]
+    fn test_polynomials_exact() {
+        // Simpson is exact for cubics, Gauss-Legendre for polynomials up to degree 9
+        let cubic = |x: PreciseDecimal| Some(x * x * x);
+        assert_eq!(simpson(cubic, pdec!(0), pdec!(2), 2), Ok(pdec!(4)));
+        let ninth = |x: PreciseDecimal| Some(x * x * x * x * x * x * x * x * x);
+        assert_close(
+            gauss_legendre(ninth, pdec!(0), pdec!(1), 1).unwrap(),
+            pdec!("0.1"),
+            ULP * pdec!(10),
+        );
+    }
+
+    #[test]
+    fn test_reversed_bounds() {
+        let f = |x: PreciseDecimal| x.exp();
+        let forward = gauss_legendre(f, pdec!(0), pdec!(1), 4).unwrap();
+        let backward = gauss_legendre(f, pdec!(1), pdec!(0), 4).unwrap();
+        assert_close(forward, -backward, ULP * pdec!(10));
+    }
+
+    #[test_case(|x| x.exp(), pdec!(0), pdec!(1), pdec!("1.718281828459045235360287471352662497"); "exp")]
+    #[test_case(|x| x.ln(), pdec!(1), pdec!(2), pdec!("0.386294361119890618834464242916353136"); "ln")]
+    #[test_case(|x| x.pow(pdec!("1.5")), pdec!(1), pdec!(4), pdec!("12.4"); "pow")]
+    #[test_case(|x| PreciseDecimal::ONE.checked_div(x.checked_mul(x)? + pdec!(1)), pdec!(0), pdec!(1), pdec!("0.785398163397448309615660845819875721"); "arctan")]
+    fn test_integration_error(
+        f: fn(PreciseDecimal) -> Option<PreciseDecimal>,
+        lower: PreciseDecimal,
+        upper: PreciseDecimal,
+        target_result: PreciseDecimal,
+    ) {
+        assert_close(
+            simpson(f, lower, upper, 200).unwrap(),
+            target_result,
+            pdec!("0.0000000001"),
+        );
+        assert_close(
+            gauss_legendre(f, lower, upper, 32).unwrap(),
+            target_result,
+            pdec!("0.00000000000000005"),
+        );
+    }
+}
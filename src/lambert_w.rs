@@ -0,0 +1,306 @@
+// Lambert W function, the inverse of w*e^w
+//
+// Method:
+//      1. Initial guess:
+//         near the branch point -1/e (x <= -0.25) the series in p = ±sqrt(2*(e*x + 1))
+//              W(x) = -1 + p - p^2/3 + 11/72*p^3 - 43/540*p^4 + ...
+//         is used with p >= 0 for W0 and p <= 0 for W-1. Otherwise the guess is seeded from ln:
+//              W0(x)  ~ ln(1+x) * (1 - ln(1 + ln(1+x)) / (2 + ln(1+x)))      for -0.25 < x <= e
+//              W(x)   ~ L1 - L2 + L2/L1, L1 = ln|x|, L2 = ln|L1|               otherwise
+//
+//      2. Halley iteration:
+//         for W0(x) with x <= e on f(w) = w*e^w - x
+//              w' = w - 2*f*(w+1) / (2*e^w*(w+1)^2 - f*(w+2))
+//         for W0(x) with x > e and W-1(x) on g(w) = w + ln|w| - ln|x| avoiding the over-/underflow of e^w
+//              w' = w - 2*g*w*(w+1) / (2*(w+1)^2 + g)
+//         The iteration stops once the step is smaller than 10^-12, Halley converging cubically.
+//
+// Special cases:
+//      1. W0(x) and W-1(x) are not defined for x < -1/e, returning None.
+//      2. W-1(x) is not defined for x >= 0, returning None.
+//      3. W0(0) = 0
+//      4. For |p| <= 0.03 the series (1) is returned directly with a truncation error below
+//              |p|^10 / 248 <= 2.3*10^-18
+//
+// Accuracy:
+//      The condition number of W grows with 1/(1+W) towards the branch point. The error is dominated
+//      by the error of e^w (2^-59) or ln (2^-58.45) within f and g:
+//              error_w0(x)  ~ 2^-58 * |w| / |1 + w|
+//              error_wm1(x) ~ 2^-58 * |w| / |1 + w|
+//      Close to the branch point the series (4) bounds the error by 2.3*10^-18.
+
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::logarithm::LogarithmPreciseDecimal;
+use crate::polynomial::horner;
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, Decimal, PreciseDecimal,
+};
+use radix_common_derive::pdec;
+
+const E: PreciseDecimal = pdec!("2.718281828459045235360287471352662497");
+const NEG_INV_E: PreciseDecimal = pdec!("-0.367879441171442321595523770161460867");
+const BRANCH_GUESS_LIMIT: PreciseDecimal = pdec!("-0.25");
+const BRANCH_SERIES_LIMIT: PreciseDecimal = pdec!("0.03");
+const HALLEY_TOLERANCE: PreciseDecimal = pdec!("0.000000000001");
+const MAX_ITERATIONS: usize = 10;
+
+// Coefficients of the series around the branch point
+const M2: PreciseDecimal = pdec!("-0.333333333333333333333333333333333333"); // -1/3
+const M3: PreciseDecimal = pdec!("0.152777777777777777777777777777777777"); // 11/72
+const M4: PreciseDecimal = pdec!("-0.079629629629629629629629629629629629"); // -43/540
+const M5: PreciseDecimal = pdec!("0.044502314814814814814814814814814814"); // 769/17280
+const M6: PreciseDecimal = pdec!("-0.025984714873603762492651381540270429"); // -221/8505
+const M7: PreciseDecimal = pdec!("0.015635632532333921222810111699000587"); // 680863/43545600
+const M8: PreciseDecimal = pdec!("-0.009616892024299431706839114246521653"); // -1963/204120
+const M9: PreciseDecimal = pdec!("0.006014543252956117860953251899753957"); // 226287557/37623398400
+
+pub trait LambertWDecimal {
+    fn lambert_w0(&self) -> Option<Decimal>;
+    fn lambert_wm1(&self) -> Option<Decimal>;
+}
+
+pub trait LambertWPreciseDecimal {
+    fn lambert_w0(&self) -> Option<PreciseDecimal>;
+    fn lambert_wm1(&self) -> Option<PreciseDecimal>;
+}
+
+/// Calculates p = sqrt(2*(e*x + 1)) for x >= -1/e
+fn branch_distance(x: PreciseDecimal) -> Option<PreciseDecimal> {
+    let distance = E.checked_mul(x)?.checked_add(PreciseDecimal::ONE)?;
+    if distance.is_negative() {
+        // e*x + 1 can only be negative due to rounding of e and -1/e
+        return Some(PreciseDecimal::ZERO);
+    }
+    distance.checked_mul(pdec!(2))?.checked_sqrt()
+}
+
+/// Evaluates the series around the branch point -1 + p - p^2/3 + ...
+fn branch_series(p: PreciseDecimal) -> Option<PreciseDecimal> {
+    horner(
+        p,
+        &[
+            pdec!(-1),
+            PreciseDecimal::ONE,
+            M2,
+            M3,
+            M4,
+            M5,
+            M6,
+            M7,
+            M8,
+            M9,
+        ],
+    )
+}
+
+/// Calculates the asymptotic guess L1 - L2 + L2/L1 with L1 = ln|x| and L2 = ln|L1|
+fn asymptotic_guess(x: PreciseDecimal) -> Option<PreciseDecimal> {
+    let l1 = x.checked_abs()?.ln()?;
+    let l2 = l1.checked_abs()?.ln()?;
+    l1.checked_sub(l2)?.checked_add(l2.checked_div(l1)?)
+}
+
+/// Halley iteration on f(w) = w*e^w - x
+fn halley_exp(x: PreciseDecimal, mut w: PreciseDecimal) -> Option<PreciseDecimal> {
+    for _ in 0..MAX_ITERATIONS {
+        let exp_w = w.exp()?;
+        let f = w.checked_mul(exp_w)?.checked_sub(x)?;
+        let w_plus_one = w.checked_add(PreciseDecimal::ONE)?;
+        let numerator = pdec!(2).checked_mul(f)?.checked_mul(w_plus_one)?;
+        let denominator = pdec!(2)
+            .checked_mul(exp_w)?
+            .checked_mul(w_plus_one.checked_mul(w_plus_one)?)?
+            .checked_sub(f.checked_mul(w.checked_add(pdec!(2))?)?)?;
+        let step = numerator.checked_div(denominator)?;
+        w = w.checked_sub(step)?;
+        if step.checked_abs()? <= HALLEY_TOLERANCE {
+            return Some(w);
+        }
+    }
+    None
+}
+
+/// Halley iteration on g(w) = w + ln|w| - ln|x|
+fn halley_ln(x: PreciseDecimal, mut w: PreciseDecimal) -> Option<PreciseDecimal> {
+    let ln_x = x.checked_abs()?.ln()?;
+    for _ in 0..MAX_ITERATIONS {
+        let g = w.checked_add(w.checked_abs()?.ln()?)?.checked_sub(ln_x)?;
+        let w_plus_one = w.checked_add(PreciseDecimal::ONE)?;
+        let numerator = pdec!(2)
+            .checked_mul(g)?
+            .checked_mul(w)?
+            .checked_mul(w_plus_one)?;
+        let denominator = pdec!(2)
+            .checked_mul(w_plus_one.checked_mul(w_plus_one)?)?
+            .checked_add(g)?;
+        let step = numerator.checked_div(denominator)?;
+        w = w.checked_sub(step)?;
+        if step.checked_abs()? <= HALLEY_TOLERANCE {
+            return Some(w);
+        }
+    }
+    None
+}
+
+impl LambertWDecimal for Decimal {
+    /// Calculates the principal branch W0 of the Lambert W function of a Decimal
+    /// Using W0 of PreciseDecimal internally
+    fn lambert_w0(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .lambert_w0()
+            .and_then(|w| w.try_into().ok())
+    }
+
+    /// Calculates the lower branch W-1 of the Lambert W function of a Decimal
+    /// Using W-1 of PreciseDecimal internally
+    fn lambert_wm1(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .lambert_wm1()
+            .and_then(|w| w.try_into().ok())
+    }
+}
+
+impl LambertWPreciseDecimal for PreciseDecimal {
+    /// Calculates the principal branch W0 of the Lambert W function of a PreciseDecimal
+    fn lambert_w0(&self) -> Option<PreciseDecimal> {
+        let x = *self;
+        if x < NEG_INV_E {
+            // special case (1)
+            return None;
+        }
+        if x.is_zero() {
+            // special case (3)
+            return Some(PreciseDecimal::ZERO);
+        }
+
+        if x <= BRANCH_GUESS_LIMIT {
+            let p = branch_distance(x)?;
+            let guess = branch_series(p)?;
+            if p <= BRANCH_SERIES_LIMIT {
+                // special case (4)
+                return Some(guess);
+            }
+            return halley_exp(x, guess);
+        }
+        if x <= E {
+            let ln_1_x = x.checked_add(PreciseDecimal::ONE)?.ln()?;
+            let correction = ln_1_x
+                .checked_add(PreciseDecimal::ONE)?
+                .ln()?
+                .checked_div(ln_1_x.checked_add(pdec!(2))?)?;
+            let guess = ln_1_x.checked_mul(PreciseDecimal::ONE.checked_sub(correction)?)?;
+            return halley_exp(x, guess);
+        }
+        halley_ln(x, asymptotic_guess(x)?)
+    }
+
+    /// Calculates the lower branch W-1 of the Lambert W function of a PreciseDecimal
+    fn lambert_wm1(&self) -> Option<PreciseDecimal> {
+        let x = *self;
+        if x < NEG_INV_E || !x.is_negative() {
+            // special cases (1) and (2)
+            return None;
+        }
+
+        if x <= BRANCH_GUESS_LIMIT {
+            let p = branch_distance(x)?;
+            let guess = branch_series(p.checked_neg()?)?;
+            if p <= BRANCH_SERIES_LIMIT {
+                // special case (4)
+                return Some(guess);
+            }
+            return halley_ln(x, guess);
+        }
+        halley_ln(x, asymptotic_guess(x)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common_derive::dec;
+    use test_case::test_case;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(M2, pdec!(-1) / pdec!(3));
+        assert_eq!(M3, pdec!(11) / pdec!(72));
+        assert_eq!(M4, pdec!(-43) / pdec!(540));
+        assert_eq!(M5, pdec!(769) / pdec!(17280));
+        assert_eq!(M6, pdec!(-221) / pdec!(8505));
+        assert_eq!(M7, pdec!(680863) / pdec!(43545600));
+        assert_eq!(M8, pdec!(-1963) / pdec!(204120));
+        assert_eq!(M9, pdec!(226287557) / pdec!(37623398400));
+    }
+
+    #[test]
+    fn test_lambert_w_domain() {
+        assert_eq!(dec!(-1).lambert_w0(), None);
+        assert_eq!(dec!("-0.368").lambert_w0(), None);
+        assert_eq!(dec!("-0.368").lambert_wm1(), None);
+        assert_eq!(dec!(0).lambert_wm1(), None);
+        assert_eq!(dec!(1).lambert_wm1(), None);
+        assert_eq!(PreciseDecimal::MIN.lambert_w0(), None);
+    }
+
+    #[test]
+    fn test_lambert_w_special_values() {
+        assert_eq!(dec!(0).lambert_w0(), Some(dec!(0)));
+        // -1/e truncated to 36 decimals lies 4.5*10^-37 above the branch point
+        assert_eq!(
+            NEG_INV_E.lambert_w0(),
+            Some(pdec!("-0.999999999999999998000000000000000002"))
+        );
+        assert_eq!(
+            NEG_INV_E.lambert_wm1(),
+            Some(pdec!("-1.000000000000000002000000000000000001"))
+        );
+    }
+
+    #[test]
+    fn test_lambert_w_inverse() {
+        for x in [pdec!("-0.3"), pdec!("0.5"), pdec!(3), pdec!(1000)] {
+            let w = x.lambert_w0().unwrap();
+            let error = (w * w.exp().unwrap() - x).checked_abs().unwrap();
+            assert!(error <= pdec!("0.000000000000000005") * x.checked_abs().unwrap());
+        }
+    }
+
+    #[test_case(pdec!("-0.3678"), pdec!("-0.979360714957828477476184443488648168"), pdec!("0.0000000000000001"); "near branch point")]
+    #[test_case(pdec!("-0.3"), pdec!("-0.489402227180214969036231251996293368"), pdec!("0.000000000000000005"); "branch guess")]
+    #[test_case(pdec!("-0.1"), pdec!("-0.111832559158962964833569456820265842"), pdec!("0.000000000000000005"); "negative")]
+    #[test_case(pdec!("0.000000000000000001"), pdec!("0.000000000000000000999999999999999999"), pdec!("0.000000000000000000000000000000000001"); "tiny")]
+    #[test_case(pdec!("0.5"), pdec!("0.351733711249195826024909300929951065"), pdec!("0.000000000000000005"); "half")]
+    #[test_case(pdec!(1), pdec!("0.567143290409783872999968662210355549"), pdec!("0.000000000000000005"); "omega")]
+    #[test_case(E, pdec!("0.999999999999999999999999999999999999"), pdec!("0.000000000000000005"); "e")]
+    #[test_case(pdec!(10), pdec!("1.745528002740699383074301264875389911"), pdec!("0.000000000000000005"); "ten")]
+    #[test_case(pdec!(1000000), pdec!("11.383358086140052622000156781585004289"), pdec!("0.000000000000000005"); "million")]
+    #[test_case(pdec!("1000000000000000000000000000000"), pdec!("64.904633770046124079605603624292833567"), pdec!("0.000000000000000005"); "large")]
+    fn test_lambert_w0_error(
+        x: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (x.lambert_w0().unwrap() - target_result)
+            .checked_abs()
+            .unwrap();
+        assert!(error <= max_error);
+    }
+
+    #[test_case(pdec!("-0.3678"), pdec!("-1.020927239409427553738750824109410264"), pdec!("0.0000000000000001"); "near branch point")]
+    #[test_case(pdec!("-0.3"), pdec!("-1.781337023421627611974170281512745260"), pdec!("0.000000000000000005"); "branch guess")]
+    #[test_case(pdec!("-0.1"), pdec!("-3.577152063957297218409391963511994880"), pdec!("0.000000000000000005"); "negative")]
+    #[test_case(pdec!("-0.000000000000000001"), pdec!("-45.258931710426509739023263877830811864"), pdec!("0.000000000000000005"); "small")]
+    #[test_case(pdec!("-0.000000000000000000000000000000000001"), pdec!("-87.363136765334218822430321622486786513"), pdec!("0.000000000000000005"); "tiny")]
+    fn test_lambert_wm1_error(
+        x: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (x.lambert_wm1().unwrap() - target_result)
+            .checked_abs()
+            .unwrap();
+        assert!(error <= max_error);
+    }
+}
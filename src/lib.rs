@@ -1,7 +1,55 @@
+// A panic aborts the whole transaction of a blueprint, therefore every public function returns None
+// or an error instead. Outside of tests all arithmetic has to be checked.
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::expect_used,
+        clippy::indexing_slicing,
+        clippy::panic,
+        clippy::todo,
+        clippy::unimplemented,
+        clippy::unreachable,
+        clippy::unwrap_used
+    )
+)]
+
+pub mod approx;
+pub mod bonding_curve;
+pub mod decay;
+pub mod error_estimation;
+pub mod error_function;
 pub mod exponential;
+pub mod gamma;
+pub mod integration;
+pub mod lambert_w;
+pub mod lmsr;
 pub mod logarithm;
+pub mod logistic;
+pub mod normal;
+pub mod options;
+pub mod polynomial;
 pub mod power;
+pub mod root_finding;
+pub mod statistics;
+pub mod twap;
 
+pub use approx::*;
+pub use bonding_curve::*;
+pub use decay::*;
+pub use error_estimation::*;
+pub use error_function::*;
 pub use exponential::*;
+pub use gamma::*;
+pub use integration::*;
+pub use lambert_w::*;
+pub use lmsr::*;
 pub use logarithm::*;
+pub use logistic::*;
+pub use normal::*;
+pub use options::*;
+pub use polynomial::*;
 pub use power::*;
+pub use root_finding::*;
+pub use statistics::*;
+pub use twap::*;
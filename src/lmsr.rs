@@ -0,0 +1,256 @@
+// Logarithmic Market Scoring Rule (LMSR)
+//
+// Method:
+//      1. Cost function of the outstanding quantities q_1, ..., q_n with liquidity b > 0:
+//              C(q) = b * ln(Σ e^(q_i/b))
+//         evaluated with log_sum_exp, which shifts the exponents by their maximum and never overflows.
+//
+//      2. Marginal prices as softmax of the scaled quantities:
+//              p_i = e^(q_i/b - m) / Σ e^(q_j/b - m),  m = max(q_j/b)
+//
+//      3. Cost of trading `amount` of outcome i (negative amounts sell):
+//              trade_cost = C(q + amount * e_i) - C(q)
+//
+// Rounding:
+//      The cost function has an absolute error of at most b * 6*10^-18 + 10^-36 (ERROR_SOFTPLUS of log_sum_exp).
+//      The trade cost is therefore calculated as the upper bound of C(q + amount * e_i) minus the
+//      lower bound of C(q), so buyers never pay less and sellers never receive more than the exact cost.
+//      The maximum loss of the market maker b * ln(n) is rounded up as well.
+//
+// Accuracy:
+//      The prices are exact up to the error of exp (2^-59 relative) and the truncation of the final
+//      division, their sum lies within n * 10^-36 below one.
+
+use crate::error_estimation::ULP;
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::logarithm::LogarithmPreciseDecimal;
+use crate::logistic::{log_sum_exp, ERROR_SOFTPLUS};
+use radix_common::math::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, PreciseDecimal};
+use radix_common::ScryptoSbor;
+
+/// LMSR market maker over a fixed number of outcomes
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct LmsrMarket {
+    liquidity: PreciseDecimal,
+    quantities: Vec<PreciseDecimal>,
+}
+
+impl LmsrMarket {
+    /// Creates a new market with liquidity parameter `liquidity` and `outcomes` outcomes without any
+    /// outstanding shares
+    ///
+    /// Returns None if `liquidity` is not positive or there are less than two outcomes.
+    pub fn new(liquidity: PreciseDecimal, outcomes: usize) -> Option<Self> {
+        if !liquidity.is_positive() || outcomes < 2 {
+            return None;
+        }
+        Some(Self {
+            liquidity,
+            quantities: vec![PreciseDecimal::ZERO; outcomes],
+        })
+    }
+
+    /// Liquidity parameter b
+    pub fn liquidity(&self) -> PreciseDecimal {
+        self.liquidity
+    }
+
+    /// Outstanding shares per outcome
+    pub fn quantities(&self) -> &[PreciseDecimal] {
+        &self.quantities
+    }
+
+    /// Cost function C(q), rounded up
+    pub fn cost(&self) -> Option<PreciseDecimal> {
+        let (cost, max_error) = self.cost_with_error(&self.quantities)?;
+        cost.checked_add(max_error)
+    }
+
+    /// Maximum loss b * ln(n) of the market maker, rounded up
+    pub fn max_loss(&self) -> Option<PreciseDecimal> {
+        let outcomes = PreciseDecimal::from(self.quantities.len() as u64);
+        self.liquidity
+            .checked_mul(outcomes.ln()?)?
+            .checked_add(self.max_error()?)
+    }
+
+    /// Marginal prices of all outcomes
+    pub fn prices(&self) -> Option<Vec<PreciseDecimal>> {
+        let scaled = self.scaled(&self.quantities)?;
+        let max = *scaled.iter().max()?;
+        let mut terms = Vec::with_capacity(scaled.len());
+        for value in scaled {
+            // differences overflowing PreciseDecimal are far below the underflow of e^x
+            let term = match value.checked_sub(max) {
+                Some(shifted) => shifted.exp()?,
+                None => PreciseDecimal::ZERO,
+            };
+            terms.push(term);
+        }
+        let sum = terms
+            .iter()
+            .try_fold(PreciseDecimal::ZERO, |sum, term| sum.checked_add(*term))?;
+        terms.iter().map(|term| term.checked_div(sum)).collect()
+    }
+
+    /// Marginal price of `outcome`
+    pub fn price(&self, outcome: usize) -> Option<PreciseDecimal> {
+        self.prices()?.get(outcome).copied()
+    }
+
+    /// Cost of buying `amount` shares of `outcome`, a negative amount sells shares
+    ///
+    /// The cost is rounded up, a negative cost is the payout of a sale rounded towards zero.
+    pub fn trade_cost(&self, outcome: usize, amount: PreciseDecimal) -> Option<PreciseDecimal> {
+        let quantities = self.traded_quantities(outcome, amount)?;
+        let (cost_before, error_before) = self.cost_with_error(&self.quantities)?;
+        let (cost_after, error_after) = self.cost_with_error(&quantities)?;
+        cost_after
+            .checked_add(error_after)?
+            .checked_sub(cost_before.checked_sub(error_before)?)
+    }
+
+    /// Buys `amount` shares of `outcome`, or sells them for a negative amount, returning the trade cost
+    pub fn trade(&mut self, outcome: usize, amount: PreciseDecimal) -> Option<PreciseDecimal> {
+        let cost = self.trade_cost(outcome, amount)?;
+        self.quantities = self.traded_quantities(outcome, amount)?;
+        Some(cost)
+    }
+
+    /// Quantities after trading `amount` shares of `outcome`
+    fn traded_quantities(
+        &self,
+        outcome: usize,
+        amount: PreciseDecimal,
+    ) -> Option<Vec<PreciseDecimal>> {
+        let mut quantities = self.quantities.clone();
+        let quantity = quantities.get_mut(outcome)?;
+        *quantity = quantity.checked_add(amount)?;
+        Some(quantities)
+    }
+
+    /// Scaled quantities q_i / b
+    fn scaled(&self, quantities: &[PreciseDecimal]) -> Option<Vec<PreciseDecimal>> {
+        quantities
+            .iter()
+            .map(|quantity| quantity.checked_div(self.liquidity))
+            .collect()
+    }
+
+    /// Maximum absolute error b * 6*10^-18 + 10^-36 of the cost function
+    fn max_error(&self) -> Option<PreciseDecimal> {
+        self.liquidity.checked_mul(ERROR_SOFTPLUS)?.checked_add(ULP)
+    }
+
+    /// Cost function C(q) of arbitrary quantities together with its maximum error
+    fn cost_with_error(
+        &self,
+        quantities: &[PreciseDecimal],
+    ) -> Option<(PreciseDecimal, PreciseDecimal)> {
+        let cost = self
+            .liquidity
+            .checked_mul(log_sum_exp(&self.scaled(quantities)?)?)?;
+        Some((cost, self.max_error()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common_derive::pdec;
+    use test_case::test_case;
+
+    fn market(liquidity: PreciseDecimal, quantities: &[PreciseDecimal]) -> LmsrMarket {
+        let mut market = LmsrMarket::new(liquidity, quantities.len()).unwrap();
+        for (outcome, quantity) in quantities.iter().enumerate() {
+            market.trade(outcome, *quantity).unwrap();
+        }
+        market
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        assert_eq!(LmsrMarket::new(pdec!(0), 2), None);
+        assert_eq!(LmsrMarket::new(pdec!(-100), 2), None);
+        assert_eq!(LmsrMarket::new(pdec!(100), 1), None);
+        assert_eq!(LmsrMarket::new(pdec!(100), 0), None);
+    }
+
+    #[test]
+    fn test_initial_state() {
+        let market = LmsrMarket::new(pdec!(100), 4).unwrap();
+        assert_eq!(market.liquidity(), pdec!(100));
+        assert_eq!(market.quantities(), &[pdec!(0); 4]);
+        assert_eq!(market.prices(), Some(vec![pdec!("0.25"); 4]));
+        assert_eq!(market.price(4), None);
+    }
+
+    #[test]
+    fn test_max_loss() {
+        let market = LmsrMarket::new(pdec!(100), 2).unwrap();
+        // 100 * ln(2) = 69.314718055994530941723212145817656807
+        let max_loss = market.max_loss().unwrap();
+        assert!(max_loss >= pdec!("69.314718055994530941723212145817656808"));
+        assert!(max_loss <= pdec!("69.314718055994531941723212145817656808"));
+        assert_eq!(market.cost(), Some(max_loss));
+    }
+
+    #[test_case(&[pdec!(0), pdec!(0)]; "equal")]
+    #[test_case(&[pdec!(10), pdec!(0), pdec!(-25)]; "mixed")]
+    #[test_case(&[pdec!(1000000), pdec!(0)]; "overflowing exponent")]
+    #[test_case(&[pdec!(5000), pdec!(4000), pdec!(4500), pdec!(5000), pdec!(1)]; "many outcomes")]
+    fn test_prices_sum_to_one(quantities: &[PreciseDecimal]) {
+        let market = market(pdec!(100), quantities);
+        let prices = market.prices().unwrap();
+        let sum = prices
+            .iter()
+            .fold(PreciseDecimal::ZERO, |sum, price| sum + *price);
+        let max_error = ULP * PreciseDecimal::from(prices.len() as u64);
+        assert!(sum <= pdec!(1));
+        assert!(pdec!(1) - sum <= max_error);
+    }
+
+    #[test]
+    fn test_prices_overflow() {
+        let market = market(pdec!(100), &[pdec!(1000000), pdec!(0)]);
+        assert_eq!(market.prices(), Some(vec![pdec!(1), pdec!(0)]));
+    }
+
+    #[test_case(&[pdec!(0), pdec!(0)], 0, pdec!(10), pdec!("5.124947951362558541286698685748147383"); "buy")]
+    #[test_case(&[pdec!(50), pdec!(0)], 0, pdec!(-20), pdec!("-11.972173971157956205840891950549417778"); "sell")]
+    #[test_case(&[pdec!(0), pdec!(20), pdec!(-10)], 2, pdec!(100), pdec!("40.368195533235828452421569995355861803"); "three outcomes")]
+    #[test_case(&[pdec!(10000), pdec!(9000)], 1, pdec!(500), pdec!("0.666994958990120396964719990333496951"); "large quantities")]
+    fn test_trade_cost_rounding(
+        quantities: &[PreciseDecimal],
+        outcome: usize,
+        amount: PreciseDecimal,
+        exact_cost: PreciseDecimal,
+    ) {
+        let market = market(pdec!(100), quantities);
+        let cost = market.trade_cost(outcome, amount).unwrap();
+        assert!(cost >= exact_cost);
+        assert!(cost - exact_cost <= pdec!("0.000000000000002"));
+    }
+
+    #[test]
+    fn test_trade_round_trip() {
+        let mut market = LmsrMarket::new(pdec!(100), 3).unwrap();
+        let buy = market.trade(1, pdec!("42.5")).unwrap();
+        assert_eq!(market.quantities(), &[pdec!(0), pdec!("42.5"), pdec!(0)]);
+        let sell = market.trade(1, pdec!("-42.5")).unwrap();
+        assert_eq!(market.quantities(), &[pdec!(0); 3]);
+        assert!(buy.is_positive());
+        assert!(sell.is_negative());
+        // the market maker never loses on a round trip
+        assert!(buy + sell >= pdec!(0));
+    }
+
+    #[test]
+    fn test_trade_invalid_outcome() {
+        let mut market = LmsrMarket::new(pdec!(100), 2).unwrap();
+        assert_eq!(market.trade(2, pdec!(1)), None);
+        assert_eq!(market.trade_cost(2, pdec!(1)), None);
+        assert_eq!(market.quantities(), &[pdec!(0); 2]);
+    }
+}
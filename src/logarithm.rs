@@ -58,14 +58,18 @@
  *      due to exact integer calculation.
  */
 
+use crate::polynomial::horner;
 use num_traits::Zero;
-use radix_common::math::{Decimal, PreciseDecimal};
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Decimal, PreciseDecimal, I256,
+};
 use radix_common_derive::pdec;
 
 const LN2: PreciseDecimal = pdec!("0.693147180559945309417232121458176568");
 const LN10: PreciseDecimal = pdec!("2.302585092994045684017991454684364207");
 const SQRT: PreciseDecimal = pdec!("1.414213562373095048801688724209698078");
 const SQRT_HALF: PreciseDecimal = pdec!("0.707106781186547524400844362104849039");
+const TWO: PreciseDecimal = pdec!(2);
 const LG1: PreciseDecimal = pdec!("0.6666666666666735130"); // 6.666666666666735130e-01
 const LG2: PreciseDecimal = pdec!("0.3999999999940941908"); // 3.999999999940941908e-01
 const LG3: PreciseDecimal = pdec!("0.2857142874366239149"); // 2.857142874366239149e-01
@@ -90,47 +94,52 @@ pub trait LogarithmPreciseDecimal {
 
 /// Reduces the argument x by finding k and f such that
 /// x = 2^k * (1+f)    where  sqrt(2)/2 < 1+f < sqrt(2)
-fn log_reduce_argument(number: PreciseDecimal) -> (i32, PreciseDecimal) {
+fn log_reduce_argument(number: PreciseDecimal) -> Option<(i32, PreciseDecimal)> {
     let one_subunits = PreciseDecimal::ONE.precise_subunits();
-    let full_integer = number.precise_subunits() / one_subunits;
+    let full_integer = number.precise_subunits().checked_div(one_subunits)?;
 
     if full_integer.is_zero() {
         if number >= SQRT_HALF {
-            return (0, number);
+            return Some((0, number));
         }
 
         // uses leading zeros of the full big integer to derive k
-        let k = number.precise_subunits().leading_zeros() as i32
-            - SQRT_HALF.precise_subunits().leading_zeros() as i32;
-        let r = number * PreciseDecimal::from_precise_subunits(one_subunits << k as u32);
+        let k = number
+            .precise_subunits()
+            .leading_zeros()
+            .checked_sub(SQRT_HALF.precise_subunits().leading_zeros())?;
+        let two_pow_k = PreciseDecimal::from_precise_subunits(I256(one_subunits.0.checked_shl(k)?));
+        let r = number.checked_mul(two_pow_k)?;
+        let k = i32::try_from(k).ok()?;
 
         if r >= SQRT_HALF {
-            return (-k, r);
+            return Some((k.checked_neg()?, r));
         }
 
         // r can be smaller than SQRT_HALF but still having the same amount of leading zeros
-        return (-k - 1, r * pdec!(2));
+        return Some((k.checked_neg()?.checked_sub(1)?, r.checked_mul(TWO)?));
     }
 
     // uses leading zeros of the full big integer to derive k
     // 255 bits only because the first bit is the sign bit
-    let k = 255 - full_integer.leading_zeros() as i32; // index highest integer bit
-    let r = number / PreciseDecimal::from_precise_subunits(one_subunits << k as u32);
+    let k = 255u32.checked_sub(full_integer.leading_zeros())?; // index highest integer bit
+    let two_pow_k = PreciseDecimal::from_precise_subunits(I256(one_subunits.0.checked_shl(k)?));
+    let r = number.checked_div(two_pow_k)?;
+    let k = i32::try_from(k).ok()?;
 
     if r <= SQRT {
-        return (k, r);
+        return Some((k, r));
     }
 
     // r can be larger than SQRT but still having the same amount of leading zeros
-    return (k + 1, r / pdec!(2));
+    Some((k.checked_add(1)?, r.checked_div(TWO)?))
 }
 
 impl LogarithmDecimal for Decimal {
     /// Calculates the natural logarithm of a Decimal
     /// Using the natural logarithm of PreciseDecimal internally
     fn ln(&self) -> Option<Decimal> {
-        PreciseDecimal::try_from(*self)
-            .ok()?
+        PreciseDecimal::from(*self)
             .ln()
             .and_then(|log| log.try_into().ok())
     }
@@ -138,8 +147,7 @@ impl LogarithmDecimal for Decimal {
     /// Calculates the logarithm to base 2 of a Decimal
     /// Using the logarithm to base 2 of PreciseDecimal internally
     fn log2(&self) -> Option<Decimal> {
-        PreciseDecimal::try_from(*self)
-            .ok()?
+        PreciseDecimal::from(*self)
             .log2()
             .and_then(|log| log.try_into().ok())
     }
@@ -147,8 +155,7 @@ impl LogarithmDecimal for Decimal {
     /// Calculates the logarithm to base 10 of a Decimal
     /// Using the logarithm to base 10 of PreciseDecimal internally
     fn log10(&self) -> Option<Decimal> {
-        PreciseDecimal::try_from(*self)
-            .ok()?
+        PreciseDecimal::from(*self)
             .log10()
             .and_then(|log| log.try_into().ok())
     }
@@ -156,9 +163,8 @@ impl LogarithmDecimal for Decimal {
     /// Calculates the logarithm to an arbitrary base of a Decimal
     /// Using the logarithm to an abitrary base of PreciseDecimal internally
     fn log_base(&self, base: Decimal) -> Option<Decimal> {
-        let base = PreciseDecimal::try_from(base).ok()?;
-        PreciseDecimal::try_from(*self)
-            .ok()?
+        let base = PreciseDecimal::from(base);
+        PreciseDecimal::from(*self)
             .log_base(base)
             .and_then(|log| log.try_into().ok())
     }
@@ -173,37 +179,40 @@ impl LogarithmPreciseDecimal for PreciseDecimal {
         }
 
         // (1) Argument Reduction
-        let (k, r) = log_reduce_argument(*self);
+        let (k, r) = log_reduce_argument(*self)?;
         // println!("k = {:?}, r = {:?}", k, r);
         // println!("x_n = {:?}", pdec!(2).checked_powi(k.into())? * r);
         // println!("x_o = {:?}", self);
 
         // (2) Approximation of log(1+f)
-        let f = r - PreciseDecimal::ONE;
-        let s = f / (pdec!(2) + f);
-        let z = s * s;
-        let w = z * z;
-        let remez = z * (LG1 + w * (LG3 + w * (LG5 + w * LG7))) + w * (LG2 + w * (LG4 + w * LG6));
-        let log_1_f = f - s * (f - remez);
+        let f = r.checked_sub(PreciseDecimal::ONE)?;
+        let s = f.checked_div(TWO.checked_add(f)?)?;
+        let z = s.checked_mul(s)?;
+        let w = z.checked_mul(z)?;
+        let t1 = z.checked_mul(horner(w, &[LG1, LG3, LG5, LG7])?)?;
+        let t2 = w.checked_mul(horner(w, &[LG2, LG4, LG6])?)?;
+        let remez = t1.checked_add(t2)?;
+        let log_1_f = f.checked_sub(s.checked_mul(f.checked_sub(remez)?)?)?;
 
         // (3) log(x) = k*ln2 + log(1+f)
-        Some(LN2 * k + log_1_f)
+        LN2.checked_mul(k)?.checked_add(log_1_f)
     }
 
     /// Calculates the logarithm to base 2 of a PreciseDecimal
     fn log2(&self) -> Option<PreciseDecimal> {
-        Some(self.ln()? / LN2)
+        self.ln()?.checked_div(LN2)
     }
 
     /// Calculates the logarithm to base 10 of a PreciseDecimal
     fn log10(&self) -> Option<PreciseDecimal> {
-        Some(self.ln()? / LN10)
+        self.ln()?.checked_div(LN10)
     }
 
     /// Calculates the logarithm to an arbitrary base of a PreciseDecimal
     fn log_base(&self, base: PreciseDecimal) -> Option<PreciseDecimal> {
         let base_ln = base.ln()?;
-        Some(self.ln()? / base_ln)
+        // ln(1) = 0, division by zero
+        self.ln()?.checked_div(base_ln)
     }
 }
 
@@ -220,6 +229,7 @@ mod tests {
         assert_eq!(LN10, pdec!("2.302585092994045684017991454684364207"));
         assert_eq!(SQRT, pdec!("1.414213562373095048801688724209698078"));
         assert_eq!(SQRT_HALF, pdec!("0.707106781186547524400844362104849039"));
+        assert_eq!(TWO, pdec!(2));
         assert_eq!(LG1, pdec!("0.6666666666666735130"));
         assert_eq!(LG2, pdec!("0.3999999999940941908"));
         assert_eq!(LG3, pdec!("0.2857142874366239149"));
@@ -450,6 +460,8 @@ mod tests {
             Decimal::MAX.log_base(dec!(8)),
             Some(dec!("43.735098097342492579"))
         );
+        assert_eq!(dec!(5).log_base(dec!(1)), None);
+        assert_eq!(pdec!(5).log_base(pdec!(1)), None);
     }
 
     #[test_case(dec!(0.000000001), dec!(-20.723265836946411157), dec!(0.000000000000000002); "tiny value")]
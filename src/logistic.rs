@@ -0,0 +1,238 @@
+// Logistic sigmoid, softplus and log-sum-exp
+//
+// Method:
+//      1. sigmoid(x) = 1 / (1 + e^-x) is evaluated with a non-positive exponent only:
+//              sigmoid(x) = 1 / (1 + e^-x)         for x >= 0
+//              sigmoid(x) = e^x / (1 + e^x)        for x < 0
+//
+//      2. softplus(x) = ln(1 + e^x) is rewritten as
+//              softplus(x) = max(x, 0) + ln(1 + e^-|x|)
+//
+//      3. log_sum_exp(x_1, ..., x_n) = ln(Σ e^x_i) is shifted by the maximum m = max(x_i)
+//              log_sum_exp(x_1, ..., x_n) = m + ln(1 + Σ_{i != argmax} e^(x_i - m))
+//
+//      4. ln(1 + y) for 0 <= y <= 2*10^-9 is calculated with the series
+//              ln(1 + y) = y - y^2/2 + y^3/3 - y^4/4
+//         keeping the relative precision of tiny y, the truncation error being below y^5/5 < 7*10^-45.
+//
+// Special cases:
+//      1. e^x is only ever evaluated for x <= 0 and underflows to zero for x < -82,
+//         hence sigmoid(x) = 1 for x > 82 and sigmoid(x) = 0 for x < -82 (up to rounding).
+//      2. softplus(x) = x for x > 82 and softplus(x) = e^x for very negative x.
+//      3. log_sum_exp of an empty slice is None.
+//
+// Accuracy:
+//      sigmoid has an absolute error below 2^-58, being dominated by the error of exp.
+//      softplus and log_sum_exp have an absolute error below ERROR_SOFTPLUS = 6*10^-18, the error 2^-58.45
+//      of ln plus the error 2^-59 of exp, which passes through ln(1 + y) with a slope below one.
+
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::logarithm::LogarithmPreciseDecimal;
+use radix_common::math::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Decimal, PreciseDecimal};
+use radix_common_derive::pdec;
+
+const LN_1P_SERIES_LIMIT: PreciseDecimal = pdec!("0.000000002");
+pub(crate) const ERROR_SOFTPLUS: PreciseDecimal = pdec!("0.000000000000000006"); // 2^-58.45 + 2^-59 rounded up
+
+pub trait LogisticDecimal {
+    fn sigmoid(&self) -> Option<Decimal>;
+    fn softplus(&self) -> Option<Decimal>;
+}
+
+pub trait LogisticPreciseDecimal {
+    fn sigmoid(&self) -> Option<PreciseDecimal>;
+    fn softplus(&self) -> Option<PreciseDecimal>;
+}
+
+/// Calculates ln(1 + y) for y >= 0
+fn ln_1p(y: PreciseDecimal) -> Option<PreciseDecimal> {
+    if y > LN_1P_SERIES_LIMIT {
+        return y.checked_add(PreciseDecimal::ONE)?.ln();
+    }
+    // (4) series
+    let yy = y.checked_mul(y)?;
+    y.checked_sub(yy.checked_div(pdec!(2))?)?
+        .checked_add(yy.checked_mul(y)?.checked_div(pdec!(3))?)?
+        .checked_sub(yy.checked_mul(yy)?.checked_div(pdec!(4))?)
+}
+
+/// Calculates -|x|, which unlike |x| never overflows
+#[allow(clippy::arithmetic_side_effects)]
+fn negative_abs(x: PreciseDecimal) -> PreciseDecimal {
+    if x.is_negative() {
+        return x;
+    }
+    -x
+}
+
+/// Calculates ln(Σ e^x_i) of a slice of PreciseDecimals without overflowing
+/// Returns None for an empty slice or if the result is not representable
+pub fn log_sum_exp(values: &[PreciseDecimal]) -> Option<PreciseDecimal> {
+    // (3) shift by the maximum
+    let (index, max) = values
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.cmp(b))?;
+    let mut sum = PreciseDecimal::ZERO;
+    for (i, value) in values.iter().enumerate() {
+        if i == index {
+            continue;
+        }
+        // x_i - m <= 0 only overflows for differences far below the underflow of e^x
+        if let Some(shifted) = value.checked_sub(max) {
+            sum = sum.checked_add(shifted.exp()?)?;
+        }
+    }
+    max.checked_add(ln_1p(sum)?)
+}
+
+impl LogisticDecimal for Decimal {
+    /// Calculates the logistic sigmoid of a Decimal
+    /// Using the sigmoid of PreciseDecimal internally
+    fn sigmoid(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .sigmoid()
+            .and_then(|sigmoid| sigmoid.try_into().ok())
+    }
+
+    /// Calculates the softplus of a Decimal
+    /// Using the softplus of PreciseDecimal internally
+    fn softplus(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .softplus()
+            .and_then(|softplus| softplus.try_into().ok())
+    }
+}
+
+impl LogisticPreciseDecimal for PreciseDecimal {
+    /// Calculates the logistic sigmoid 1 / (1 + e^-x) of a PreciseDecimal
+    fn sigmoid(&self) -> Option<PreciseDecimal> {
+        // (1) non-positive exponent
+        let exp = negative_abs(*self).exp()?;
+        let denominator = exp.checked_add(PreciseDecimal::ONE)?;
+        if self.is_negative() {
+            return exp.checked_div(denominator);
+        }
+        PreciseDecimal::ONE.checked_div(denominator)
+    }
+
+    /// Calculates the softplus ln(1 + e^x) of a PreciseDecimal
+    fn softplus(&self) -> Option<PreciseDecimal> {
+        // (2) non-positive exponent
+        let exp = negative_abs(*self).exp()?;
+        let ln_1p_exp = ln_1p(exp)?;
+        if self.is_negative() {
+            return Some(ln_1p_exp);
+        }
+        self.checked_add(ln_1p_exp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::ApproxPreciseDecimal;
+    use crate::error_estimation::error_ln;
+    use pretty_assertions::assert_eq;
+    use radix_common_derive::dec;
+    use test_case::test_case;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(LN_1P_SERIES_LIMIT, pdec!("0.000000002"));
+        // 2^-59 is the error of exp for non-positive arguments
+        assert!(ERROR_SOFTPLUS >= error_ln() + pdec!("0.000000000000000001734723475976807095"));
+    }
+
+    #[test]
+    fn test_sigmoid_special_values() {
+        assert_eq!(dec!(0).sigmoid(), Some(dec!("0.5")));
+        assert_eq!(dec!(100).sigmoid(), Some(dec!(1)));
+        assert_eq!(dec!(-100).sigmoid(), Some(dec!(0)));
+        assert_eq!(PreciseDecimal::MAX.sigmoid(), Some(pdec!(1)));
+        assert_eq!(PreciseDecimal::MIN.sigmoid(), Some(pdec!(0)));
+    }
+
+    #[test]
+    fn test_sigmoid_symmetry() {
+        for x in [pdec!("0.1"), pdec!(1), pdec!("4.2"), pdec!(30)] {
+            let sum = x.sigmoid().unwrap() + (-x).sigmoid().unwrap();
+            assert!(sum.approx_eq_abs(pdec!(1), pdec!("0.000000000000000001")));
+        }
+    }
+
+    #[test_case(pdec!(1), pdec!("0.731058578630004879251159241821836274"), pdec!("0.000000000000000004"); "one")]
+    #[test_case(pdec!(-1), pdec!("0.268941421369995120748840758178163725"), pdec!("0.000000000000000004"); "minus one")]
+    #[test_case(pdec!("4.2"), pdec!("0.985225968306726942249465999133115806"), pdec!("0.000000000000000004"); "positive")]
+    #[test_case(pdec!(-40), pdec!("0.000000000000000004248354255291588977"), pdec!("0.000000000000000000000000000000000100"); "small")]
+    #[test_case(pdec!(95), pdec!("0.999999999999999999999999999999999999"), pdec!("0.000000000000000000000000000000000001"); "large")]
+    fn test_sigmoid_error(
+        x: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (x.sigmoid().unwrap() - target_result)
+            .checked_abs()
+            .unwrap();
+        assert!(error <= max_error);
+    }
+
+    #[test]
+    fn test_softplus_special_values() {
+        assert_eq!(dec!(100).softplus(), Some(dec!(100)));
+        assert_eq!(dec!(-100).softplus(), Some(dec!(0)));
+        assert_eq!(PreciseDecimal::MAX.softplus(), Some(PreciseDecimal::MAX));
+        assert_eq!(PreciseDecimal::MIN.softplus(), Some(pdec!(0)));
+    }
+
+    #[test_case(pdec!(0), pdec!("0.693147180559945309417232121458176568"), pdec!("0.000000000000000006"); "zero")]
+    #[test_case(pdec!(1), pdec!("1.313261687518222834048995494967855641"), pdec!("0.000000000000000006"); "one")]
+    #[test_case(pdec!(-1), pdec!("0.313261687518222834048995494967855641"), pdec!("0.000000000000000006"); "minus one")]
+    #[test_case(pdec!(50), pdec!("50.000000000000000000000192874984796391"), pdec!("0.000000000000000000000000000000000100"); "large")]
+    #[test_case(pdec!(-40), pdec!("0.000000000000000004248354255291588986"), pdec!("0.000000000000000000000000000000000100"); "small")]
+    fn test_softplus_error(
+        x: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (x.softplus().unwrap() - target_result)
+            .checked_abs()
+            .unwrap();
+        assert!(error <= max_error);
+    }
+
+    #[test]
+    fn test_log_sum_exp_special_values() {
+        assert_eq!(log_sum_exp(&[]), None);
+        assert_eq!(log_sum_exp(&[pdec!(5)]), Some(pdec!(5)));
+        assert_eq!(
+            log_sum_exp(&[PreciseDecimal::MAX]),
+            Some(PreciseDecimal::MAX)
+        );
+        assert_eq!(
+            log_sum_exp(&[PreciseDecimal::MIN, PreciseDecimal::MAX]),
+            Some(PreciseDecimal::MAX)
+        );
+        assert_eq!(
+            log_sum_exp(&[PreciseDecimal::MAX, PreciseDecimal::MAX]),
+            None
+        );
+    }
+
+    #[test_case(&[pdec!(0), pdec!(0)], pdec!("0.693147180559945309417232121458176568"), pdec!("0.000000000000000006"); "ln two")]
+    #[test_case(&[pdec!(1), pdec!(2), pdec!(3)], pdec!("3.407605964444380304482919904545070451"), pdec!("0.000000000000000006"); "small")]
+    #[test_case(&[pdec!(1000), pdec!(1001), pdec!(999)], pdec!("1001.407605964444380304482919904545070451"), pdec!("0.000000000000000006"); "large")]
+    #[test_case(&[pdec!(-1000), pdec!(-1000)], pdec!("-999.306852819440054690582767878541823431"), pdec!("0.000000000000000006"); "negative")]
+    #[test_case(&[pdec!(0), pdec!(-40)], pdec!("0.000000000000000004248354255291588986"), pdec!("0.000000000000000000000000000000000100"); "tiny difference")]
+    fn test_log_sum_exp_error(
+        values: &[PreciseDecimal],
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (log_sum_exp(values).unwrap() - target_result)
+            .checked_abs()
+            .unwrap();
+        assert!(error <= max_error);
+    }
+}
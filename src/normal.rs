@@ -0,0 +1,305 @@
+// Standard normal distribution
+//
+// Method:
+//      1. Probability density function:
+//              φ(x) = e^(-x^2/2) / sqrt(2π)
+//
+//      2. Cumulative distribution function via the complementary error function:
+//              Φ(x) = erfc(-x/sqrt(2)) / 2
+//         Using erfc instead of 1 + erf keeps full absolute precision in the lower tail.
+//         The upper half is calculated by symmetry Φ(x) = 1 - Φ(-x).
+//
+//      3. Inverse cumulative distribution function (probit):
+//         By symmetry Φ^-1(p) = -Φ^-1(1-p), which is exact for fixed point decimals,
+//         so only p <= 0.5 has to be solved.
+//         (a) Initial guess with the rational approximation by P. J. Acklam
+//             (relative error below 1.15e-9)
+//         (b) For 0.02425 <= p <= 0.5 one Halley step on Φ(x) - p, tripling the number of correct digits:
+//                  e = Φ(x) - p
+//                  u = e * sqrt(2π) * e^(x^2/2)
+//                  x = x - u / (1 + x*u/2)
+//         (c) For p < 0.02425 the absolute precision of Φ(x) cannot resolve p, so two Newton steps
+//             are taken on ln Φ(x) - ln p instead, using the tail form of erfc with z = -x/sqrt(2):
+//                  ln Φ(x) = ln(z * erfc(z)) - ln(z) - ln(2)
+//                  ln(z * erfc(z)) = -z^2 - 0.5625 + R/S
+//                  d/dx ln Φ(x) = φ(x)/Φ(x) = 2z * e^(-z^2 - ln(z * erfc(z))) / sqrt(2π)
+//             Each step squares the relative error of the guess, as ln Φ is close to linear in the tail.
+//
+// Special cases:
+//      Φ^-1(0) and Φ^-1(1) are -INF and +INF, returning None.
+//
+// Accuracy:
+//      The absolute error of Φ is bound by the error of erfc (s_erf.c), which is below 2^-57 (~ 6.9*10^-18).
+//      The absolute error of Φ^-1 is below 10^-16 for 0.02425 <= p <= 0.975, being
+//      the error of Φ divided by φ(x) >= 0.057. In the tails the error of ln Φ(x) - ln p, twice
+//      the error 2^-58.45 of ln plus the error of R/S, is divided by φ(x)/Φ(x) >= 2.3, which keeps
+//      the absolute error below 3*10^-18.
+
+use crate::error_function::{erfc_tail_exponent, ErrorFunctionPreciseDecimal};
+use crate::exponential::{ExponentialPreciseDecimal, LN2};
+use crate::logarithm::LogarithmPreciseDecimal;
+use crate::polynomial::horner;
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, Decimal, PreciseDecimal,
+};
+use radix_common_derive::pdec;
+
+const HALF: PreciseDecimal = pdec!("0.5");
+const SQRT_2PI: PreciseDecimal = pdec!("2.506628274631000502415765284811045253");
+const INV_SQRT_2PI: PreciseDecimal = pdec!("0.398942280401432677939946059934381868");
+const INV_SQRT2: PreciseDecimal = pdec!("0.707106781186547524400844362104849039");
+const PDF_CUTOFF: PreciseDecimal = pdec!(13); // e^(-13^2/2) underflows
+const PROBIT_LOW: PreciseDecimal = pdec!("0.02425");
+
+// Coefficients of the rational approximation of the inverse normal CDF by P. J. Acklam
+const A1: PreciseDecimal = pdec!("-39.69683028665376"); // -3.969683028665376e+01
+const A2: PreciseDecimal = pdec!("220.9460984245205"); // 2.209460984245205e+02
+const A3: PreciseDecimal = pdec!("-275.9285104469687"); // -2.759285104469687e+02
+const A4: PreciseDecimal = pdec!("138.3577518672690"); // 1.383577518672690e+02
+const A5: PreciseDecimal = pdec!("-30.66479806614716"); // -3.066479806614716e+01
+const A6: PreciseDecimal = pdec!("2.506628277459239"); // 2.506628277459239e+00
+const B1: PreciseDecimal = pdec!("-54.47609879822406"); // -5.447609879822406e+01
+const B2: PreciseDecimal = pdec!("161.5858368580409"); // 1.615858368580409e+02
+const B3: PreciseDecimal = pdec!("-155.6989798598866"); // -1.556989798598866e+02
+const B4: PreciseDecimal = pdec!("66.80131188771972"); // 6.680131188771972e+01
+const B5: PreciseDecimal = pdec!("-13.28068155288572"); // -1.328068155288572e+01
+const C1: PreciseDecimal = pdec!("-0.007784894002430293"); // -7.784894002430293e-03
+const C2: PreciseDecimal = pdec!("-0.3223964580411365"); // -3.223964580411365e-01
+const C3: PreciseDecimal = pdec!("-2.400758277161838"); // -2.400758277161838e+00
+const C4: PreciseDecimal = pdec!("-2.549732539343734"); // -2.549732539343734e+00
+const C5: PreciseDecimal = pdec!("4.374664141464968"); // 4.374664141464968e+00
+const C6: PreciseDecimal = pdec!("2.938163982698783"); // 2.938163982698783e+00
+const D1: PreciseDecimal = pdec!("0.007784695709041462"); // 7.784695709041462e-03
+const D2: PreciseDecimal = pdec!("0.3224671290700398"); // 3.224671290700398e-01
+const D3: PreciseDecimal = pdec!("2.445134137142996"); // 2.445134137142996e+00
+const D4: PreciseDecimal = pdec!("3.754408661907416"); // 3.754408661907416e+00
+
+pub trait NormalDistributionDecimal {
+    fn normal_pdf(&self) -> Option<Decimal>;
+    fn normal_cdf(&self) -> Option<Decimal>;
+    fn normal_inverse_cdf(&self) -> Option<Decimal>;
+}
+
+pub trait NormalDistributionPreciseDecimal {
+    fn normal_pdf(&self) -> Option<PreciseDecimal>;
+    fn normal_cdf(&self) -> Option<PreciseDecimal>;
+    fn normal_inverse_cdf(&self) -> Option<PreciseDecimal>;
+}
+
+/// Initial guess of Φ^-1(p) for 0 < p <= 0.5 by P. J. Acklam
+fn probit_guess(p: PreciseDecimal) -> Option<PreciseDecimal> {
+    if p < PROBIT_LOW {
+        let q = pdec!(-2).checked_mul(p.ln()?)?.checked_sqrt()?;
+        let numerator = horner(q, &[C6, C5, C4, C3, C2, C1])?;
+        let denominator = horner(q, &[PreciseDecimal::ONE, D4, D3, D2, D1])?;
+        return numerator.checked_div(denominator);
+    }
+    let q = p.checked_sub(HALF)?;
+    let r = q.checked_mul(q)?;
+    let numerator = horner(r, &[A6, A5, A4, A3, A2, A1])?.checked_mul(q)?;
+    let denominator = horner(r, &[PreciseDecimal::ONE, B5, B4, B3, B2, B1])?;
+    numerator.checked_div(denominator)
+}
+
+/// Newton step on ln Φ(x) - ln p for x <= -1.25*sqrt(2)
+fn probit_tail_step(x: PreciseDecimal, ln_p: PreciseDecimal) -> Option<PreciseDecimal> {
+    let z = x.checked_mul(INV_SQRT2.checked_neg()?)?;
+    let exponent = erfc_tail_exponent(z)?;
+    let ln_cdf = exponent.checked_sub(z.ln()?)?.checked_sub(LN2)?;
+    let ratio = z
+        .checked_mul(z)?
+        .checked_add(exponent)?
+        .checked_neg()?
+        .exp()?;
+    let slope = z
+        .checked_mul(pdec!(2))?
+        .checked_mul(INV_SQRT_2PI)?
+        .checked_mul(ratio)?;
+    x.checked_sub(ln_cdf.checked_sub(ln_p)?.checked_div(slope)?)
+}
+
+impl NormalDistributionDecimal for Decimal {
+    /// Calculates the standard normal probability density function of a Decimal
+    /// Using the standard normal probability density function of PreciseDecimal internally
+    fn normal_pdf(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .normal_pdf()
+            .and_then(|pdf| pdf.try_into().ok())
+    }
+
+    /// Calculates the standard normal cumulative distribution function of a Decimal
+    /// Using the standard normal cumulative distribution function of PreciseDecimal internally
+    fn normal_cdf(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .normal_cdf()
+            .and_then(|cdf| cdf.try_into().ok())
+    }
+
+    /// Calculates the inverse standard normal cumulative distribution function of a Decimal
+    /// Using the inverse standard normal cumulative distribution function of PreciseDecimal internally
+    fn normal_inverse_cdf(&self) -> Option<Decimal> {
+        PreciseDecimal::from(*self)
+            .normal_inverse_cdf()
+            .and_then(|x| x.try_into().ok())
+    }
+}
+
+impl NormalDistributionPreciseDecimal for PreciseDecimal {
+    /// Calculates the standard normal probability density function of a PreciseDecimal
+    fn normal_pdf(&self) -> Option<PreciseDecimal> {
+        // compared on both sides as |x| overflows for MIN
+        if *self < PDF_CUTOFF.checked_neg()? || *self > PDF_CUTOFF {
+            return Some(PreciseDecimal::ZERO);
+        }
+        let exponent = self
+            .checked_mul(*self)?
+            .checked_neg()?
+            .checked_div(pdec!(2))?;
+        exponent.exp()?.checked_mul(INV_SQRT_2PI)
+    }
+
+    /// Calculates the standard normal cumulative distribution function of a PreciseDecimal
+    fn normal_cdf(&self) -> Option<PreciseDecimal> {
+        if self.is_positive() {
+            // Φ(x) = 1 - Φ(-x) keeps the symmetry exact despite truncation
+            return PreciseDecimal::ONE.checked_sub(self.checked_neg()?.normal_cdf()?);
+        }
+        let x = self.checked_mul(INV_SQRT2.checked_neg()?)?;
+        x.erfc()?.checked_div(pdec!(2))
+    }
+
+    /// Calculates the inverse standard normal cumulative distribution function (probit) of a PreciseDecimal
+    fn normal_inverse_cdf(&self) -> Option<PreciseDecimal> {
+        if !self.is_positive() || *self >= PreciseDecimal::ONE {
+            return None;
+        }
+        if *self > HALF {
+            return PreciseDecimal::ONE
+                .checked_sub(*self)?
+                .normal_inverse_cdf()?
+                .checked_neg();
+        }
+        if *self == HALF {
+            return Some(PreciseDecimal::ZERO);
+        }
+
+        // (a) Initial guess
+        let x = probit_guess(*self)?;
+
+        if *self < PROBIT_LOW {
+            // (c) Newton refinement of ln Φ
+            let ln_p = self.ln()?;
+            return probit_tail_step(probit_tail_step(x, ln_p)?, ln_p);
+        }
+
+        // (b) Halley refinement
+        let e = x.normal_cdf()?.checked_sub(*self)?;
+        let u = e
+            .checked_mul(SQRT_2PI)?
+            .checked_mul(x.checked_mul(x)?.checked_div(pdec!(2))?.exp()?)?;
+        let step = u.checked_div(
+            PreciseDecimal::ONE.checked_add(x.checked_mul(u)?.checked_div(pdec!(2))?)?,
+        )?;
+        x.checked_sub(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common_derive::dec;
+    use test_case::test_case;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(HALF, pdec!("0.5"));
+        assert_eq!(SQRT_2PI, pdec!("2.506628274631000502415765284811045253"));
+        assert_eq!(
+            INV_SQRT_2PI,
+            pdec!("0.398942280401432677939946059934381868")
+        );
+        assert_eq!(INV_SQRT2, pdec!("0.707106781186547524400844362104849039"));
+        assert_eq!(PROBIT_LOW, pdec!("0.02425"));
+    }
+
+    #[test]
+    fn test_normal_pdf() {
+        assert_eq!(dec!(0).normal_pdf(), Some(dec!("0.398942280401432677")));
+        assert_eq!(dec!(1).normal_pdf(), dec!(-1).normal_pdf());
+        assert_eq!(dec!(1).normal_pdf(), Some(dec!("0.241970724519143349")));
+        assert_eq!(pdec!(14).normal_pdf(), Some(pdec!(0)));
+        assert_eq!(PreciseDecimal::MAX.normal_pdf(), Some(pdec!(0)));
+        assert_eq!(PreciseDecimal::MIN.normal_pdf(), Some(pdec!(0)));
+        assert_eq!(Decimal::MIN.normal_pdf(), Some(dec!(0)));
+    }
+
+    #[test]
+    fn test_normal_cdf_symmetry() {
+        assert_eq!(dec!(0).normal_cdf(), Some(dec!("0.5")));
+        assert_eq!(pdec!(0).normal_cdf(), Some(pdec!("0.5")));
+        let x = pdec!("1.2345");
+        assert_eq!(
+            x.normal_cdf().unwrap() + (-x).normal_cdf().unwrap(),
+            pdec!(1)
+        );
+    }
+
+    #[test]
+    fn test_normal_cdf_extreme() {
+        assert_eq!(dec!(-40).normal_cdf(), Some(dec!(0)));
+        assert_eq!(dec!(40).normal_cdf(), Some(dec!(1)));
+        assert_eq!(Decimal::MIN.normal_cdf(), Some(dec!(0)));
+        assert_eq!(Decimal::MAX.normal_cdf(), Some(dec!(1)));
+        assert_eq!(PreciseDecimal::MIN.normal_cdf(), Some(pdec!(0)));
+        assert_eq!(PreciseDecimal::MAX.normal_cdf(), Some(pdec!(1)));
+    }
+
+    #[test_case(pdec!(-8), pdec!("0.000000000000000622096057427178412351"); "lower tail")]
+    #[test_case(pdec!(-3), pdec!("0.001349898031630094526651814767594977"); "three sigma below")]
+    #[test_case(pdec!(-1), pdec!("0.158655253931457051414767454367962077"); "one sigma below")]
+    #[test_case(pdec!("0.5"), pdec!("0.691462461274013103637704610608337739"); "half sigma above")]
+    #[test_case(pdec!("1.96"), pdec!("0.975002104851779565863415730959162809"); "97.5 percent quantile")]
+    #[test_case(pdec!(6), pdec!("0.999999999013412354962301859299135867"); "upper tail")]
+    fn test_normal_cdf_error(x: PreciseDecimal, target_result: PreciseDecimal) {
+        let error = (x.normal_cdf().unwrap() - target_result)
+            .checked_abs()
+            .unwrap();
+        assert!(error <= pdec!("0.000000000000000004"));
+    }
+
+    #[test]
+    fn test_normal_inverse_cdf_invalid() {
+        assert_eq!(dec!(0).normal_inverse_cdf(), None);
+        assert_eq!(dec!(1).normal_inverse_cdf(), None);
+        assert_eq!(dec!(-1).normal_inverse_cdf(), None);
+        assert_eq!(dec!(2).normal_inverse_cdf(), None);
+    }
+
+    #[test]
+    fn test_normal_inverse_cdf_symmetry() {
+        assert_eq!(dec!("0.5").normal_inverse_cdf(), Some(dec!(0)));
+        assert_eq!(
+            pdec!("0.9").normal_inverse_cdf().unwrap(),
+            -pdec!("0.1").normal_inverse_cdf().unwrap()
+        );
+    }
+
+    #[test_case(pdec!("0.000000000000000000000000000000000001"), pdec!("-12.604276277268244520872758326958347922"), pdec!("0.000000000000000003"); "smallest PreciseDecimal")]
+    #[test_case(pdec!("0.000000000000000000000000000001"), pdec!("-11.464024688443615726982264221236037244"), pdec!("0.000000000000000003"); "far tail")]
+    #[test_case(pdec!("0.000000000000000001"), pdec!("-8.757290348782315063881128622142082818"), pdec!("0.000000000000000003"); "smallest Decimal")]
+    #[test_case(pdec!("0.001"), pdec!("-3.090232306167813541540399830107379205"), pdec!("0.000000000000000003"); "lower tail")]
+    #[test_case(pdec!("0.02"), pdec!("-2.053748910631823052937351657740453446"), pdec!("0.000000000000000003"); "tail start")]
+    #[test_case(pdec!("0.3"), pdec!("-0.524400512708040784038289325025122554"), pdec!("0.000000000000000010"); "central region")]
+    #[test_case(pdec!("0.975"), pdec!("1.959963984540054235524594430520551527"), pdec!("0.000000000000000010"); "97.5 percent quantile")]
+    fn test_normal_inverse_cdf_error(
+        p: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (p.normal_inverse_cdf().unwrap() - target_result)
+            .checked_abs()
+            .unwrap();
+        assert!(error <= max_error);
+    }
+}
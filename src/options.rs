@@ -0,0 +1,570 @@
+// Black-Scholes option pricing
+//
+// Method:
+//      1. With spot S, strike K, risk-free rate r, volatility σ and time to expiry T (in years):
+//              d1 = (ln(S/K) + (r + σ^2/2)*T) / (σ*sqrt(T))
+//              d2 = d1 - σ*sqrt(T)
+//
+//      2. Prices:
+//              call = S*Φ(d1) - K*e^(-rT)*Φ(d2)
+//              put  = K*e^(-rT)*Φ(-d2) - S*Φ(-d1)
+//
+//      3. Greeks (theta per year):
+//              delta_call = Φ(d1)
+//              delta_put  = Φ(d1) - 1
+//              gamma      = φ(d1) / (S*σ*sqrt(T))
+//              vega       = S*φ(d1)*sqrt(T)
+//              theta_call = -S*φ(d1)*σ / (2*sqrt(T)) - r*K*e^(-rT)*Φ(d2)
+//              theta_put  = -S*φ(d1)*σ / (2*sqrt(T)) + r*K*e^(-rT)*Φ(-d2)
+//         with Φ and φ the standard normal CDF and PDF of the normal module.
+//
+//      4. Implied volatility is solved with Newton's method using vega as derivative, safeguarded by
+//         bisection whenever a Newton step leaves the current bracket.
+//
+// Special cases:
+//      For T = 0 or σ = 0 the model degenerates (d1 and d2 are undefined), which is reported as an error.
+
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::logarithm::LogarithmPreciseDecimal;
+use crate::normal::NormalDistributionPreciseDecimal;
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, PreciseDecimal,
+};
+use radix_common_derive::pdec;
+
+const INV_SQRT_2PI: PreciseDecimal = pdec!("0.398942280401432677939946059934381868");
+const IMPLIED_VOLATILITY_MIN: PreciseDecimal = pdec!("0.000001");
+const IMPLIED_VOLATILITY_MAX: PreciseDecimal = pdec!(10);
+const IMPLIED_VOLATILITY_TOLERANCE: PreciseDecimal = pdec!("0.000000000000000001");
+const IMPLIED_VOLATILITY_MAX_ITERATIONS: u32 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptionsError {
+    NonPositiveSpot,
+    NonPositiveStrike,
+    NonPositiveTime,
+    NonPositiveVolatility,
+    /// The option price lies outside of the no-arbitrage bounds, so no implied volatility exists
+    PriceOutOfBounds,
+    /// The implied volatility solver did not converge within the maximum number of iterations
+    NoConvergence,
+    /// An intermediate result was not representable
+    Overflow,
+}
+
+/// Black-Scholes model of a European option
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlackScholes {
+    pub spot: PreciseDecimal,
+    pub strike: PreciseDecimal,
+    pub rate: PreciseDecimal,
+    pub volatility: PreciseDecimal,
+    /// Time to expiry in years
+    pub time: PreciseDecimal,
+}
+
+/// Intermediate terms shared by prices and greeks
+struct Terms {
+    d1: PreciseDecimal,
+    d2: PreciseDecimal,
+    sqrt_time: PreciseDecimal,
+    discounted_strike: PreciseDecimal,
+}
+
+impl BlackScholes {
+    pub fn new(
+        spot: PreciseDecimal,
+        strike: PreciseDecimal,
+        rate: PreciseDecimal,
+        volatility: PreciseDecimal,
+        time: PreciseDecimal,
+    ) -> Result<Self, OptionsError> {
+        let model = Self {
+            spot,
+            strike,
+            rate,
+            volatility,
+            time,
+        };
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Checks the parameters, which is repeated for every calculation as the fields are public
+    fn validate(&self) -> Result<(), OptionsError> {
+        if !self.spot.is_positive() {
+            return Err(OptionsError::NonPositiveSpot);
+        }
+        if !self.strike.is_positive() {
+            return Err(OptionsError::NonPositiveStrike);
+        }
+        if !self.time.is_positive() {
+            return Err(OptionsError::NonPositiveTime);
+        }
+        if !self.volatility.is_positive() {
+            return Err(OptionsError::NonPositiveVolatility);
+        }
+        Ok(())
+    }
+
+    fn terms(&self) -> Result<Terms, OptionsError> {
+        self.validate()?;
+        self.checked_terms().ok_or(OptionsError::Overflow)
+    }
+
+    fn checked_terms(&self) -> Option<Terms> {
+        let sqrt_time = self.time.checked_sqrt()?;
+        let vol_sqrt_time = self.volatility.checked_mul(sqrt_time)?;
+        let drift = self
+            .rate
+            .checked_add(
+                self.volatility
+                    .checked_mul(self.volatility)?
+                    .checked_div(pdec!(2))?,
+            )?
+            .checked_mul(self.time)?;
+        let d1 = self
+            .spot
+            .checked_div(self.strike)?
+            .ln()?
+            .checked_add(drift)?
+            .checked_div(vol_sqrt_time)?;
+        let d2 = d1.checked_sub(vol_sqrt_time)?;
+        let discounted_strike = self
+            .strike
+            .checked_mul(self.rate.checked_mul(self.time)?.checked_neg()?.exp()?)?;
+        Some(Terms {
+            d1,
+            d2,
+            sqrt_time,
+            discounted_strike,
+        })
+    }
+
+    /// Calculates the price of the option
+    pub fn price(&self, option_type: OptionType) -> Result<PreciseDecimal, OptionsError> {
+        let t = self.terms()?;
+        match option_type {
+            OptionType::Call => (|| {
+                self.spot
+                    .checked_mul(t.d1.normal_cdf()?)?
+                    .checked_sub(t.discounted_strike.checked_mul(t.d2.normal_cdf()?)?)
+            })(),
+            OptionType::Put => (|| {
+                t.discounted_strike
+                    .checked_mul(t.d2.checked_neg()?.normal_cdf()?)?
+                    .checked_sub(self.spot.checked_mul(t.d1.checked_neg()?.normal_cdf()?)?)
+            })(),
+        }
+        .ok_or(OptionsError::Overflow)
+    }
+
+    /// Calculates the sensitivity of the option price to the spot price
+    pub fn delta(&self, option_type: OptionType) -> Result<PreciseDecimal, OptionsError> {
+        let t = self.terms()?;
+        let cdf = t.d1.normal_cdf().ok_or(OptionsError::Overflow)?;
+        match option_type {
+            OptionType::Call => Ok(cdf),
+            OptionType::Put => cdf
+                .checked_sub(PreciseDecimal::ONE)
+                .ok_or(OptionsError::Overflow),
+        }
+    }
+
+    /// Calculates the sensitivity of delta to the spot price, equal for calls and puts
+    pub fn gamma(&self) -> Result<PreciseDecimal, OptionsError> {
+        let t = self.terms()?;
+        (|| {
+            t.d1.normal_pdf()?.checked_div(
+                self.spot
+                    .checked_mul(self.volatility)?
+                    .checked_mul(t.sqrt_time)?,
+            )
+        })()
+        .ok_or(OptionsError::Overflow)
+    }
+
+    /// Calculates the sensitivity of the option price to the volatility, equal for calls and puts
+    pub fn vega(&self) -> Result<PreciseDecimal, OptionsError> {
+        let t = self.terms()?;
+        (|| {
+            self.spot
+                .checked_mul(t.d1.normal_pdf()?)?
+                .checked_mul(t.sqrt_time)
+        })()
+        .ok_or(OptionsError::Overflow)
+    }
+
+    /// Calculates the sensitivity of the option price to the passage of time, per year
+    pub fn theta(&self, option_type: OptionType) -> Result<PreciseDecimal, OptionsError> {
+        let t = self.terms()?;
+        (|| {
+            let decay = self
+                .spot
+                .checked_mul(t.d1.normal_pdf()?)?
+                .checked_mul(self.volatility)?
+                .checked_div(t.sqrt_time.checked_mul(pdec!(2))?)?
+                .checked_neg()?;
+            let carry = self.rate.checked_mul(t.discounted_strike)?;
+            match option_type {
+                OptionType::Call => decay.checked_sub(carry.checked_mul(t.d2.normal_cdf()?)?),
+                OptionType::Put => {
+                    decay.checked_add(carry.checked_mul(t.d2.checked_neg()?.normal_cdf()?)?)
+                }
+            }
+        })()
+        .ok_or(OptionsError::Overflow)
+    }
+}
+
+/// Solves the volatility for which the Black-Scholes price equals `price`
+pub fn implied_volatility(
+    option_type: OptionType,
+    price: PreciseDecimal,
+    spot: PreciseDecimal,
+    strike: PreciseDecimal,
+    rate: PreciseDecimal,
+    time: PreciseDecimal,
+) -> Result<PreciseDecimal, OptionsError> {
+    let model = |volatility| BlackScholes::new(spot, strike, rate, volatility, time);
+    let model_price = |volatility| model(volatility)?.price(option_type);
+    let bisect = |low: PreciseDecimal, high: PreciseDecimal| {
+        low.checked_add(high)
+            .and_then(|sum| sum.checked_div(pdec!(2)))
+            .ok_or(OptionsError::Overflow)
+    };
+
+    // the price is strictly increasing in the volatility
+    let (mut low, mut high) = (IMPLIED_VOLATILITY_MIN, IMPLIED_VOLATILITY_MAX);
+    if price <= model_price(low)? || price >= model_price(high)? {
+        return Err(OptionsError::PriceOutOfBounds);
+    }
+
+    // Brenner-Subrahmanyam approximation sqrt(2π/T) * price / spot as initial guess
+    let volatility = price
+        .checked_div(spot)
+        .and_then(|v| v.checked_div(time.checked_sqrt()?))
+        .and_then(|v| v.checked_div(INV_SQRT_2PI))
+        .filter(|v| *v > low && *v < high);
+    let mut volatility = match volatility {
+        Some(volatility) => volatility,
+        None => bisect(low, high)?,
+    };
+
+    for _ in 0..IMPLIED_VOLATILITY_MAX_ITERATIONS {
+        let diff = model_price(volatility)?
+            .checked_sub(price)
+            .ok_or(OptionsError::Overflow)?;
+        if diff.checked_abs().ok_or(OptionsError::Overflow)? <= IMPLIED_VOLATILITY_TOLERANCE {
+            return Ok(volatility);
+        }
+        if diff.is_positive() {
+            high = volatility;
+        } else {
+            low = volatility;
+        }
+        let newton = model(volatility)?
+            .vega()
+            .ok()
+            .and_then(|vega| diff.checked_div(vega))
+            .and_then(|step| volatility.checked_sub(step))
+            .filter(|v| *v > low && *v < high);
+        volatility = match newton {
+            Some(newton) => newton,
+            None => bisect(low, high)?,
+        };
+        if high.checked_sub(low).ok_or(OptionsError::Overflow)? <= IMPLIED_VOLATILITY_TOLERANCE {
+            return Ok(volatility);
+        }
+    }
+    Err(OptionsError::NoConvergence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(
+            INV_SQRT_2PI,
+            pdec!("0.398942280401432677939946059934381868")
+        );
+    }
+
+    // the error of Φ (< 2^-57) is scaled by spot and strike
+    fn assert_close(
+        result: PreciseDecimal,
+        target_result: PreciseDecimal,
+        max_error: PreciseDecimal,
+    ) {
+        let error = (result - target_result).checked_abs().unwrap();
+        assert!(
+            error <= max_error,
+            "{} differs from {} by {}",
+            result,
+            target_result,
+            error
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert_eq!(
+            BlackScholes::new(
+                pdec!(0),
+                pdec!(40),
+                pdec!("0.1"),
+                pdec!("0.2"),
+                pdec!("0.5")
+            ),
+            Err(OptionsError::NonPositiveSpot)
+        );
+        assert_eq!(
+            BlackScholes::new(
+                pdec!(42),
+                pdec!(0),
+                pdec!("0.1"),
+                pdec!("0.2"),
+                pdec!("0.5")
+            ),
+            Err(OptionsError::NonPositiveStrike)
+        );
+        assert_eq!(
+            BlackScholes::new(pdec!(42), pdec!(40), pdec!("0.1"), pdec!("0.2"), pdec!(0)),
+            Err(OptionsError::NonPositiveTime)
+        );
+        assert_eq!(
+            BlackScholes::new(pdec!(42), pdec!(40), pdec!("0.1"), pdec!(0), pdec!("0.5")),
+            Err(OptionsError::NonPositiveVolatility)
+        );
+        assert_eq!(
+            BlackScholes::new(
+                pdec!(42),
+                pdec!(40),
+                pdec!("0.1"),
+                pdec!("-0.2"),
+                pdec!("0.5")
+            ),
+            Err(OptionsError::NonPositiveVolatility)
+        );
+    }
+
+    #[test]
+    fn test_invalid_struct_literal() {
+        // a struct literal skips `new`, every calculation validates the parameters again
+        let model = BlackScholes {
+            spot: pdec!(42),
+            strike: pdec!(40),
+            rate: pdec!("0.1"),
+            volatility: pdec!("0.2"),
+            time: pdec!(0),
+        };
+        assert_eq!(
+            model.price(OptionType::Call),
+            Err(OptionsError::NonPositiveTime)
+        );
+        assert_eq!(
+            model.delta(OptionType::Put),
+            Err(OptionsError::NonPositiveTime)
+        );
+        assert_eq!(
+            model.theta(OptionType::Call),
+            Err(OptionsError::NonPositiveTime)
+        );
+
+        let model = BlackScholes {
+            volatility: pdec!(0),
+            time: pdec!("0.5"),
+            ..model
+        };
+        assert_eq!(
+            model.price(OptionType::Put),
+            Err(OptionsError::NonPositiveVolatility)
+        );
+        assert_eq!(model.gamma(), Err(OptionsError::NonPositiveVolatility));
+        assert_eq!(model.vega(), Err(OptionsError::NonPositiveVolatility));
+    }
+
+    #[test]
+    fn test_price_hull_example() {
+        // Hull, Options, Futures, and Other Derivatives, Example 15.6: c = 4.76, p = 0.81
+        let model = BlackScholes::new(
+            pdec!(42),
+            pdec!(40),
+            pdec!("0.1"),
+            pdec!("0.2"),
+            pdec!("0.5"),
+        )
+        .unwrap();
+        assert_close(
+            model.price(OptionType::Call).unwrap(),
+            pdec!("4.759422392871533219600728462610566579"),
+            pdec!("0.000000000000001"),
+        );
+        assert_close(
+            model.price(OptionType::Put).unwrap(),
+            pdec!("0.808599372900093583257741253796653006"),
+            pdec!("0.000000000000001"),
+        );
+    }
+
+    #[test]
+    fn test_greeks_hull_example() {
+        // Hull, Options, Futures, and Other Derivatives, Chapter 19:
+        // delta = 0.522, gamma = 0.066, vega = 12.1, theta = -4.31 (per year)
+        let model = BlackScholes::new(
+            pdec!(49),
+            pdec!(50),
+            pdec!("0.05"),
+            pdec!("0.2"),
+            pdec!("0.3846"),
+        )
+        .unwrap();
+        let max_error = pdec!("0.000000000000001");
+        assert_close(
+            model.price(OptionType::Call).unwrap(),
+            pdec!("2.400461086965661405050497269045259119"),
+            max_error,
+        );
+        assert_close(
+            model.delta(OptionType::Call).unwrap(),
+            pdec!("0.521601633971576128690545500672654422"),
+            max_error,
+        );
+        assert_close(
+            model.delta(OptionType::Put).unwrap(),
+            pdec!("-0.478398366028423871309454499327345577"),
+            max_error,
+        );
+        assert_close(
+            model.gamma().unwrap(),
+            pdec!("0.065545377252478673424272762836148633"),
+            max_error,
+        );
+        assert_close(
+            model.vega().unwrap(),
+            pdec!("12.105242754243843603067941262573083419"),
+            max_error,
+        );
+        assert_close(
+            model.theta(OptionType::Call).unwrap(),
+            pdec!("-4.305389964546104342872889684587597742"),
+            max_error,
+        );
+        assert_close(
+            model.theta(OptionType::Put).unwrap(),
+            pdec!("-1.853005672196867496978904327689312858"),
+            max_error,
+        );
+    }
+
+    #[test]
+    fn test_put_call_parity() {
+        let model = BlackScholes::new(
+            pdec!(100),
+            pdec!(100),
+            pdec!("0.05"),
+            pdec!("0.2"),
+            pdec!(1),
+        )
+        .unwrap();
+        let call = model.price(OptionType::Call).unwrap();
+        let put = model.price(OptionType::Put).unwrap();
+        let discounted_strike = pdec!(100) * pdec!("-0.05").exp().unwrap();
+        assert_close(
+            call - put,
+            pdec!(100) - discounted_strike,
+            pdec!("0.000000000000002"),
+        );
+        assert_close(
+            call,
+            pdec!("10.450583572185566781651231209678335279"),
+            pdec!("0.000000000000002"),
+        );
+        assert_close(
+            put,
+            pdec!("5.573526022256967690793763187643551345"),
+            pdec!("0.000000000000002"),
+        );
+    }
+
+    #[test]
+    fn test_deep_out_of_the_money() {
+        let model = BlackScholes::new(
+            pdec!(1),
+            pdec!(1000),
+            pdec!("0.05"),
+            pdec!("0.2"),
+            pdec!("0.1"),
+        )
+        .unwrap();
+        assert_eq!(model.price(OptionType::Call), Ok(pdec!(0)));
+        assert_eq!(model.delta(OptionType::Call), Ok(pdec!(0)));
+    }
+
+    #[test_case(OptionType::Call, pdec!(42), pdec!(40), pdec!("0.1"), pdec!("0.2"), pdec!("0.5"); "hull call")]
+    #[test_case(OptionType::Put, pdec!(42), pdec!(40), pdec!("0.1"), pdec!("0.2"), pdec!("0.5"); "hull put")]
+    #[test_case(OptionType::Call, pdec!(100), pdec!(100), pdec!("0.05"), pdec!("0.8"), pdec!(1); "high volatility")]
+    #[test_case(OptionType::Put, pdec!(100), pdec!(102), pdec!(0), pdec!("0.05"), pdec!("0.25"); "low volatility")]
+    fn test_implied_volatility(
+        option_type: OptionType,
+        spot: PreciseDecimal,
+        strike: PreciseDecimal,
+        rate: PreciseDecimal,
+        volatility: PreciseDecimal,
+        time: PreciseDecimal,
+    ) {
+        let price = BlackScholes::new(spot, strike, rate, volatility, time)
+            .unwrap()
+            .price(option_type)
+            .unwrap();
+        let implied = implied_volatility(option_type, price, spot, strike, rate, time).unwrap();
+        assert_close(implied, volatility, pdec!("0.000000000001"));
+    }
+
+    #[test]
+    fn test_implied_volatility_out_of_bounds() {
+        assert_eq!(
+            implied_volatility(
+                OptionType::Call,
+                pdec!(43),
+                pdec!(42),
+                pdec!(40),
+                pdec!("0.1"),
+                pdec!("0.5")
+            ),
+            Err(OptionsError::PriceOutOfBounds)
+        );
+        assert_eq!(
+            implied_volatility(
+                OptionType::Call,
+                pdec!(1),
+                pdec!(42),
+                pdec!(40),
+                pdec!("0.1"),
+                pdec!("0.5")
+            ),
+            Err(OptionsError::PriceOutOfBounds)
+        );
+        assert_eq!(
+            implied_volatility(
+                OptionType::Call,
+                pdec!(4),
+                pdec!(42),
+                pdec!(40),
+                pdec!("0.1"),
+                pdec!(0)
+            ),
+            Err(OptionsError::NonPositiveTime)
+        );
+    }
+}
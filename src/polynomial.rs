@@ -0,0 +1,420 @@
+// Polynomial and rational function evaluation
+//
+// Method:
+//      1. Horner's scheme for p(x) = c_0 + c_1*x + ... + c_n*x^n:
+//              v_n = c_n
+//              v_i = c_i + x*v_i+1
+//
+//      2. Estrin's scheme pairs the coefficients and squares x on every level:
+//              p(x) = (c_0 + c_1*x) + (c_2 + c_3*x)*x^2 + ((c_4 + c_5*x) + (c_6 + c_7*x)*x^2)*x^4 + ...
+//         The levels are independent of each other, which keeps the chain of multiplications short,
+//         but the powers x^2, x^4, ... make the rounding error harder to bound.
+//
+//      3. Rational functions r(x) = p(x) / q(x) divide the Horner results.
+//
+//      4. Derivatives:
+//              p'(x) = c_1 + 2*c_2*x + ... + n*c_n*x^(n-1)
+//              r'(x) = (p'(x)*q(x) - p(x)*q'(x)) / q(x)^2
+//
+// Accuracy:
+//      Every multiplication truncates at most one ulp of 10^-36, additions are exact. The error of the
+//      Horner scheme therefore follows the recursion
+//              e_n = 0
+//              e_i = |x|*e_i+1 + 10^-36
+//      which is evaluated alongside the value and rounded up. For the rational function with the
+//      errors e_p and e_q of numerator and denominator:
+//              |r - p/q| <= (e_p + |r|*e_q) / (|q| - e_q) + 10^-36
+//      The coefficients themselves are taken as exact, the error of a fitted approximation has to be
+//      added by the caller.
+
+use crate::error_estimation::ULP;
+use radix_common::math::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, PreciseDecimal};
+use radix_common::ScryptoSbor;
+
+/// Result of an evaluation together with its maximum rounding error
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Evaluation {
+    pub value: PreciseDecimal,
+    pub max_error: PreciseDecimal,
+}
+
+/// Polynomial c_0 + c_1*x + ... + c_n*x^n with PreciseDecimal coefficients
+#[derive(ScryptoSbor, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Polynomial {
+    /// Coefficients in ascending order of the powers, without trailing zeros
+    coefficients: Vec<PreciseDecimal>,
+}
+
+/// Rational function p(x) / q(x)
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct Rational {
+    numerator: Polynomial,
+    denominator: Polynomial,
+}
+
+/// Evaluates c_0 + c_1*x + ... + c_n*x^n with Horner's scheme, the coefficients in ascending order
+pub(crate) fn horner(x: PreciseDecimal, coefficients: &[PreciseDecimal]) -> Option<PreciseDecimal> {
+    coefficients
+        .iter()
+        .rev()
+        .try_fold(PreciseDecimal::ZERO, |value, c| {
+            c.checked_add(x.checked_mul(value)?)
+        })
+}
+
+impl Polynomial {
+    /// Creates a polynomial from its coefficients in ascending order of the powers, i.e. `c_0` first
+    pub fn new(mut coefficients: Vec<PreciseDecimal>) -> Self {
+        while coefficients.last().is_some_and(|c| c.is_zero()) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    /// Coefficients in ascending order of the powers
+    pub fn coefficients(&self) -> &[PreciseDecimal] {
+        &self.coefficients
+    }
+
+    /// Degree of the polynomial, zero for constants and the zero polynomial
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    /// Returns true for the zero polynomial
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's scheme
+    pub fn horner(&self, x: PreciseDecimal) -> Option<PreciseDecimal> {
+        horner(x, &self.coefficients)
+    }
+
+    /// Evaluates the polynomial at `x` with Estrin's scheme
+    pub fn estrin(&self, x: PreciseDecimal) -> Option<PreciseDecimal> {
+        let mut level = self.coefficients.clone();
+        let mut power = x;
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [low, high] => low.checked_add(high.checked_mul(power)?),
+                    [low] => Some(*low),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            if level.len() > 1 {
+                power = power.checked_mul(power)?;
+            }
+        }
+        Some(level.first().copied().unwrap_or(PreciseDecimal::ZERO))
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's scheme together with its maximum rounding error
+    pub fn evaluate(&self, x: PreciseDecimal) -> Option<Evaluation> {
+        let abs_x = x.checked_abs()?;
+        let mut value = PreciseDecimal::ZERO;
+        let mut max_error = PreciseDecimal::ZERO;
+        for (i, c) in self.coefficients.iter().enumerate().rev() {
+            value = c.checked_add(x.checked_mul(value)?)?;
+            if i < self.degree() {
+                // one ulp for the multiplication above and one for rounding up |x|*e
+                max_error = abs_x
+                    .checked_mul(max_error)?
+                    .checked_add(ULP)?
+                    .checked_add(ULP)?;
+            }
+        }
+        Some(Evaluation { value, max_error })
+    }
+
+    /// Derivative of the polynomial
+    pub fn derivative(&self) -> Option<Polynomial> {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| c.checked_mul(PreciseDecimal::from(i as u64)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Polynomial::new(coefficients))
+    }
+
+    /// Sum of two polynomials
+    pub fn checked_add(&self, other: &Polynomial) -> Option<Polynomial> {
+        self.zip_with(other, |a, b| a.checked_add(b))
+    }
+
+    /// Difference of two polynomials
+    pub fn checked_sub(&self, other: &Polynomial) -> Option<Polynomial> {
+        self.zip_with(other, |a, b| a.checked_sub(b))
+    }
+
+    /// Product of two polynomials, exact apart from the truncation of the coefficient products
+    pub fn checked_mul(&self, other: &Polynomial) -> Option<Polynomial> {
+        if self.is_zero() || other.is_zero() {
+            return Some(Polynomial::default());
+        }
+        let len = self.degree().checked_add(other.coefficients.len())?;
+        let mut coefficients = vec![PreciseDecimal::ZERO; len];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                let coefficient = coefficients.get_mut(i.checked_add(j)?)?;
+                *coefficient = coefficient.checked_add(a.checked_mul(*b)?)?;
+            }
+        }
+        Some(Polynomial::new(coefficients))
+    }
+
+    /// Combines the coefficients of equal powers, padding the shorter polynomial with zeros
+    fn zip_with(
+        &self,
+        other: &Polynomial,
+        f: impl Fn(PreciseDecimal, PreciseDecimal) -> Option<PreciseDecimal>,
+    ) -> Option<Polynomial> {
+        let len = self.coefficients.len().max(other.coefficients.len());
+        let coefficient = |coefficients: &[PreciseDecimal], i: usize| {
+            coefficients.get(i).copied().unwrap_or(PreciseDecimal::ZERO)
+        };
+        let coefficients = (0..len)
+            .map(|i| {
+                f(
+                    coefficient(&self.coefficients, i),
+                    coefficient(&other.coefficients, i),
+                )
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Polynomial::new(coefficients))
+    }
+}
+
+impl Rational {
+    /// Creates the rational function `numerator / denominator`
+    ///
+    /// Returns None if the denominator is the zero polynomial.
+    pub fn new(numerator: Polynomial, denominator: Polynomial) -> Option<Self> {
+        if denominator.is_zero() {
+            return None;
+        }
+        Some(Self {
+            numerator,
+            denominator,
+        })
+    }
+
+    /// Numerator p(x)
+    pub fn numerator(&self) -> &Polynomial {
+        &self.numerator
+    }
+
+    /// Denominator q(x)
+    pub fn denominator(&self) -> &Polynomial {
+        &self.denominator
+    }
+
+    /// Evaluates the rational function at `x`, None if the denominator vanishes
+    pub fn horner(&self, x: PreciseDecimal) -> Option<PreciseDecimal> {
+        self.numerator
+            .horner(x)?
+            .checked_div(self.denominator.horner(x)?)
+    }
+
+    /// Evaluates the rational function at `x` together with its maximum rounding error
+    ///
+    /// Returns None if the denominator is not separated from zero by its own rounding error.
+    pub fn evaluate(&self, x: PreciseDecimal) -> Option<Evaluation> {
+        let p = self.numerator.evaluate(x)?;
+        let q = self.denominator.evaluate(x)?;
+        let abs_q = q.value.checked_abs()?;
+        if abs_q <= q.max_error {
+            return None;
+        }
+        let value = p.value.checked_div(q.value)?;
+        // (e_p + |r|*e_q) / (|q| - e_q) + ulp, every truncation rounded up by one ulp
+        let numerator = value
+            .checked_abs()?
+            .checked_mul(q.max_error)?
+            .checked_add(ULP)?
+            .checked_add(p.max_error)?;
+        let max_error = numerator
+            .checked_div(abs_q.checked_sub(q.max_error)?)?
+            .checked_add(ULP)?
+            .checked_add(ULP)?;
+        Some(Evaluation { value, max_error })
+    }
+
+    /// Derivative (p'q - pq') / q^2 of the rational function
+    pub fn derivative(&self) -> Option<Rational> {
+        let numerator = self
+            .numerator
+            .derivative()?
+            .checked_mul(&self.denominator)?
+            .checked_sub(
+                &self
+                    .numerator
+                    .checked_mul(&self.denominator.derivative()?)?,
+            )?;
+        let denominator = self.denominator.checked_mul(&self.denominator)?;
+        Rational::new(numerator, denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common::data::scrypto::{scrypto_decode, scrypto_encode};
+    use radix_common_derive::pdec;
+    use test_case::test_case;
+
+    fn polynomial(coefficients: &[PreciseDecimal]) -> Polynomial {
+        Polynomial::new(coefficients.to_vec())
+    }
+
+    /// Taylor polynomial of e^x of degree 12 with truncated coefficients 1/k!
+    fn exp_taylor() -> Polynomial {
+        polynomial(&[
+            pdec!(1),
+            pdec!(1),
+            pdec!("0.5"),
+            pdec!("0.166666666666666666666666666666666666"),
+            pdec!("0.041666666666666666666666666666666666"),
+            pdec!("0.008333333333333333333333333333333333"),
+            pdec!("0.001388888888888888888888888888888888"),
+            pdec!("0.000198412698412698412698412698412698"),
+            pdec!("0.000024801587301587301587301587301587"),
+            pdec!("0.000002755731922398589065255731922398"),
+            pdec!("0.000000275573192239858906525573192239"),
+            pdec!("0.000000025052108385441718775052108385"),
+            pdec!("0.000000002087675698786809897921009032"),
+        ])
+    }
+
+    #[test]
+    fn test_new() {
+        let p = polynomial(&[pdec!(1), pdec!(2), pdec!(0), pdec!(0)]);
+        assert_eq!(p.coefficients(), &[pdec!(1), pdec!(2)]);
+        assert_eq!(p.degree(), 1);
+        let zero = polynomial(&[pdec!(0)]);
+        assert!(zero.is_zero());
+        assert_eq!(zero.degree(), 0);
+        assert_eq!(zero.horner(pdec!(5)), Some(pdec!(0)));
+        assert_eq!(zero.estrin(pdec!(5)), Some(pdec!(0)));
+    }
+
+    #[test_case(&[pdec!(1), pdec!(2), pdec!(3)], pdec!(2), pdec!(17); "quadratic")]
+    #[test_case(&[pdec!(-1), pdec!(0), pdec!(0), pdec!(1)], pdec!(-3), pdec!(-28); "cubic")]
+    #[test_case(&[pdec!(1), pdec!(1), pdec!(1), pdec!(1), pdec!(1)], pdec!("0.5"), pdec!("1.9375"); "quartic")]
+    #[test_case(&[pdec!(7)], pdec!(1000), pdec!(7); "constant")]
+    fn test_horner_estrin(
+        coefficients: &[PreciseDecimal],
+        x: PreciseDecimal,
+        target: PreciseDecimal,
+    ) {
+        let p = polynomial(coefficients);
+        assert_eq!(p.horner(x), Some(target));
+        assert_eq!(p.estrin(x), Some(target));
+        let evaluation = p.evaluate(x).unwrap();
+        assert_eq!(evaluation.value, target);
+    }
+
+    #[test_case(pdec!("0.3"), pdec!("1.349858807576003077820616883116883116"); "0.3")]
+    #[test_case(pdec!("-0.7"), pdec!("0.496585303792891140323957164234942012"); "-0.7")]
+    #[test_case(pdec!("0.333333333333333333333333333333333333"), pdec!("1.395612425086089425449036602694508484"); "1/3")]
+    fn test_evaluate_error(x: PreciseDecimal, exact: PreciseDecimal) {
+        // exact is the value of exp_taylor() with exact arithmetic, truncated
+        let p = exp_taylor();
+        let evaluation = p.evaluate(x).unwrap();
+        let error = (evaluation.value - exact).checked_abs().unwrap();
+        assert!(error <= evaluation.max_error + ULP);
+        assert!(evaluation.max_error <= pdec!("0.00000000000000000000000000000000005"));
+        let estrin_error = (p.estrin(x).unwrap() - exact).checked_abs().unwrap();
+        assert!(estrin_error <= pdec!("0.00000000000000000000000000000000005"));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let p = polynomial(&[pdec!(0), pdec!(0), PreciseDecimal::MAX]);
+        assert_eq!(p.horner(pdec!(2)), None);
+        assert_eq!(p.estrin(pdec!(2)), None);
+        assert_eq!(p.evaluate(pdec!(2)), None);
+    }
+
+    #[test]
+    fn test_derivative() {
+        let p = polynomial(&[pdec!(1), pdec!(2), pdec!(3), pdec!("0.5")]);
+        assert_eq!(
+            p.derivative(),
+            Some(polynomial(&[pdec!(2), pdec!(6), pdec!("1.5")]))
+        );
+        assert_eq!(
+            polynomial(&[pdec!(5)]).derivative(),
+            Some(Polynomial::default())
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let p = polynomial(&[pdec!(1), pdec!(1)]);
+        let q = polynomial(&[pdec!(-1), pdec!(1)]);
+        assert_eq!(p.checked_add(&q), Some(polynomial(&[pdec!(0), pdec!(2)])));
+        assert_eq!(p.checked_sub(&q), Some(polynomial(&[pdec!(2)])));
+        assert_eq!(
+            p.checked_mul(&q),
+            Some(polynomial(&[pdec!(-1), pdec!(0), pdec!(1)]))
+        );
+        assert_eq!(p.checked_sub(&p), Some(Polynomial::default()));
+        assert_eq!(
+            p.checked_mul(&Polynomial::default()),
+            Some(Polynomial::default())
+        );
+    }
+
+    #[test]
+    fn test_rational() {
+        // (1 + x) / (1 - x)
+        let r = Rational::new(
+            polynomial(&[pdec!(1), pdec!(1)]),
+            polynomial(&[pdec!(1), pdec!(-1)]),
+        )
+        .unwrap();
+        assert_eq!(r.numerator(), &polynomial(&[pdec!(1), pdec!(1)]));
+        assert_eq!(r.denominator(), &polynomial(&[pdec!(1), pdec!(-1)]));
+        assert_eq!(r.horner(pdec!("0.5")), Some(pdec!(3)));
+        assert_eq!(r.horner(pdec!(1)), None);
+        assert_eq!(r.evaluate(pdec!(1)), None);
+        // 2 / (1 - x)^2
+        assert_eq!(r.derivative().unwrap().horner(pdec!("0.5")), Some(pdec!(8)));
+        assert_eq!(
+            Rational::new(polynomial(&[pdec!(1)]), Polynomial::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rational_evaluate_error() {
+        // (1 + x + x^2/3) / (1 - x + x^2/3)
+        let third = pdec!("0.333333333333333333333333333333333333");
+        let r = Rational::new(
+            polynomial(&[pdec!(1), pdec!(1), third]),
+            polynomial(&[pdec!(1), pdec!(-1), third]),
+        )
+        .unwrap();
+        let x = pdec!("0.7");
+        // with the truncated coefficient third
+        let exact = pdec!("4.021582733812949640287769784172661871");
+        let evaluation = r.evaluate(x).unwrap();
+        let error = (evaluation.value - exact).checked_abs().unwrap();
+        assert!(error <= evaluation.max_error + ULP);
+        assert!(evaluation.max_error <= pdec!("0.00000000000000000000000000000000005"));
+    }
+
+    #[test]
+    fn test_sbor_roundtrip() {
+        let r = Rational::new(exp_taylor(), polynomial(&[pdec!(2), pdec!(-1)])).unwrap();
+        let encoded = scrypto_encode(&r).unwrap();
+        let decoded: Rational = scrypto_decode(&encoded).unwrap();
+        assert_eq!(decoded, r);
+    }
+}
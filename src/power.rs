@@ -55,7 +55,7 @@
 use crate::exponential::ExponentialPreciseDecimal;
 use crate::logarithm::LogarithmPreciseDecimal;
 use num_traits::ToPrimitive;
-use radix_common::math::{CheckedMul, Decimal, PreciseDecimal};
+use radix_common::math::{CheckedDiv, CheckedMul, CheckedNeg, Decimal, PreciseDecimal};
 use radix_common_derive::pdec;
 
 pub trait PowerDecimal {
@@ -70,9 +70,8 @@ impl PowerDecimal for Decimal {
     /// Calculates the power of a Decimal
     /// Using the natural logarithm of PreciseDecimal internally
     fn pow(&self, exp: Decimal) -> Option<Decimal> {
-        let exp = PreciseDecimal::try_from(exp).ok()?;
-        PreciseDecimal::try_from(*self)
-            .ok()?
+        let exp = PreciseDecimal::from(exp);
+        PreciseDecimal::from(*self)
             .pow(exp)
             .and_then(|e| e.try_into().ok())
     }
@@ -100,38 +99,40 @@ impl PowerPreciseDecimal for PreciseDecimal {
         }
         if exp == PreciseDecimal::ONE {
             // special case (20)
-            return Some(self.clone());
+            return Some(*self);
         }
         if exp == pdec!(-1) {
             // special case (21)
-            return Some(PreciseDecimal::ONE / *self);
+            return PreciseDecimal::ONE.checked_div(*self);
         }
 
         if self.is_negative() {
             let one_subunits = PreciseDecimal::ONE.precise_subunits();
-            let exp_is_integer = PreciseDecimal::from_precise_subunits(
-                exp.precise_subunits() / one_subunits * one_subunits,
-            ) == exp;
+            let exp_integer = exp.precise_subunits().checked_div(one_subunits)?;
+            let exp_is_integer =
+                PreciseDecimal::from_precise_subunits(exp_integer.checked_mul(one_subunits)?)
+                    == exp;
             if !exp_is_integer {
                 // special case (23)
                 return None;
             }
             // special case (22)
-            let is_even = (exp.precise_subunits() / one_subunits).to_i32()? % 2 == 0;
-            let pow = (self.checked_abs()?.ln()? * exp).exp();
+            let is_even = exp_integer.to_i32()?.checked_rem(2)? == 0;
+            let pow = self.checked_abs()?.ln()?.checked_mul(exp)?.exp()?;
             if is_even {
-                return pow;
+                return Some(pow);
             }
-            return Some(pdec!(-1) * pow?);
+            return pow.checked_neg();
         }
 
-        Some((self.ln()?.checked_mul(exp))?.exp()?)
+        (self.ln()?.checked_mul(exp))?.exp()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::approx::ApproxPreciseDecimal;
     use pretty_assertions::assert_eq;
     use radix_common_derive::dec;
     use test_case::test_case;
@@ -223,6 +224,7 @@ mod tests {
         assert_eq!(Decimal::MIN.pow(dec!(3)), None);
         assert_eq!(Decimal::MIN.pow(Decimal::MIN), None);
         assert_eq!(Decimal::MIN.pow(Decimal::MAX), None);
+        assert_eq!(PreciseDecimal::MIN.pow(pdec!(3)), None);
     }
 
     #[test]
@@ -274,7 +276,7 @@ mod tests {
     fn test_pow_sqrt_pdec(number: PreciseDecimal, exact_result: PreciseDecimal) {
         let sqrt_pow = number.pow(pdec!(0.5)).unwrap();
         let sqrt_native = number.checked_sqrt().unwrap();
-        assert!((sqrt_pow - exact_result).checked_abs().unwrap() < pdec!(0.000000000000000001)); // pow has a maximum precision of roughly Decimal precision
+        assert!(sqrt_pow.approx_eq_abs(exact_result, pdec!(0.000000000000000001))); // pow has a maximum precision of roughly Decimal precision
         assert_eq!(sqrt_native, exact_result); // native sqrt is more precise and can handle full PreciseDecimal precision
     }
 }
@@ -0,0 +1,511 @@
+// Root-finding solvers for f(x) = 0
+//
+// Method:
+//      1. Newton:      x' = x - f(x) / f'(x)
+//      2. Halley:      x' = x - 2*f(x)*f'(x) / (2*f'(x)^2 - f(x)*f''(x))
+//      3. Bisection:   halves a bracket [a, b] with f(a)*f(b) < 0 keeping the sign change
+//      4. Brent:       inverse quadratic interpolation or secant steps within a bracket [a, b],
+//                      falling back to bisection whenever the interpolation converges too slowly
+//                      (R. P. Brent, Algorithms for Minimization without Derivatives, 1973, zeroin)
+//
+// Termination:
+//      Newton and Halley stop once a step is not larger than `tolerance`, returning the updated x.
+//      Bisection and Brent stop once the bracket is not wider than 2*`tolerance`.
+//      All solvers stop as soon as f(x) = 0 exactly.
+//      Every solver performs at most `max_iterations` iterations with a fixed number of function
+//      evaluations each, bounding the cost of a transaction:
+//              Newton: f, f'     Halley: f, f', f''     Bisection: f     Brent: f
+//      (bisection and Brent evaluate f twice more on the initial bracket).
+//
+// Special cases:
+//      1. Newton and Halley fail with ZeroDerivative if the step cannot be divided.
+//      2. Bisection and Brent fail with NoSignChange unless f(a) and f(b) have opposite signs.
+
+use radix_common::math::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedSub, PreciseDecimal,
+};
+use radix_common_derive::pdec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RootFindingError {
+    /// The function or one of its derivatives returned None
+    EvaluationFailed,
+    /// The derivative (or Halley's denominator) is zero
+    ZeroDerivative,
+    /// The function has the same sign at both ends of the bracket
+    NoSignChange,
+    /// No root was found within the maximum number of iterations
+    NoConvergence,
+    /// An intermediate result was not representable
+    Overflow,
+}
+
+/// Evaluates a function, mapping None to EvaluationFailed
+fn evaluate(
+    f: &impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    x: PreciseDecimal,
+) -> Result<PreciseDecimal, RootFindingError> {
+    f(x).ok_or(RootFindingError::EvaluationFailed)
+}
+
+/// Whether two non-zero values have the same sign
+fn same_sign(a: PreciseDecimal, b: PreciseDecimal) -> bool {
+    a.is_negative() == b.is_negative()
+}
+
+/// Solves f(x) = 0 with Newton's method starting at `x0`
+pub fn newton(
+    f: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    df: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    x0: PreciseDecimal,
+    tolerance: PreciseDecimal,
+    max_iterations: u32,
+) -> Result<PreciseDecimal, RootFindingError> {
+    let mut x = x0;
+    for _ in 0..max_iterations {
+        let fx = evaluate(&f, x)?;
+        if fx.is_zero() {
+            return Ok(x);
+        }
+        let step = fx
+            .checked_div(evaluate(&df, x)?)
+            .ok_or(RootFindingError::ZeroDerivative)?;
+        x = x.checked_sub(step).ok_or(RootFindingError::Overflow)?;
+        if step.checked_abs().ok_or(RootFindingError::Overflow)? <= tolerance {
+            return Ok(x);
+        }
+    }
+    Err(RootFindingError::NoConvergence)
+}
+
+/// Calculates Halley's step 2*f*f' / (2*f'^2 - f*f'')
+fn halley_step(
+    fx: PreciseDecimal,
+    dfx: PreciseDecimal,
+    d2fx: PreciseDecimal,
+) -> Result<PreciseDecimal, RootFindingError> {
+    let numerator = pdec!(2)
+        .checked_mul(fx)
+        .and_then(|v| v.checked_mul(dfx))
+        .ok_or(RootFindingError::Overflow)?;
+    let denominator = dfx
+        .checked_mul(dfx)
+        .and_then(|v| v.checked_mul(pdec!(2)))
+        .and_then(|v| v.checked_sub(fx.checked_mul(d2fx)?))
+        .ok_or(RootFindingError::Overflow)?;
+    numerator
+        .checked_div(denominator)
+        .ok_or(RootFindingError::ZeroDerivative)
+}
+
+/// Solves f(x) = 0 with Halley's method starting at `x0`
+pub fn halley(
+    f: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    df: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    d2f: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    x0: PreciseDecimal,
+    tolerance: PreciseDecimal,
+    max_iterations: u32,
+) -> Result<PreciseDecimal, RootFindingError> {
+    let mut x = x0;
+    for _ in 0..max_iterations {
+        let fx = evaluate(&f, x)?;
+        if fx.is_zero() {
+            return Ok(x);
+        }
+        let dfx = evaluate(&df, x)?;
+        let d2fx = evaluate(&d2f, x)?;
+        let step = halley_step(fx, dfx, d2fx)?;
+        x = x.checked_sub(step).ok_or(RootFindingError::Overflow)?;
+        if step.checked_abs().ok_or(RootFindingError::Overflow)? <= tolerance {
+            return Ok(x);
+        }
+    }
+    Err(RootFindingError::NoConvergence)
+}
+
+/// Solves f(x) = 0 by bisection of the bracket [`lower`, `upper`]
+pub fn bisection(
+    f: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    lower: PreciseDecimal,
+    upper: PreciseDecimal,
+    tolerance: PreciseDecimal,
+    max_iterations: u32,
+) -> Result<PreciseDecimal, RootFindingError> {
+    let (mut a, mut b) = (lower, upper);
+    let fa = evaluate(&f, a)?;
+    let fb = evaluate(&f, b)?;
+    if fa.is_zero() {
+        return Ok(a);
+    }
+    if fb.is_zero() {
+        return Ok(b);
+    }
+    if same_sign(fa, fb) {
+        // special case (2)
+        return Err(RootFindingError::NoSignChange);
+    }
+    let mut fa = fa;
+    for _ in 0..max_iterations {
+        let half_width = b
+            .checked_sub(a)
+            .and_then(|width| width.checked_div(pdec!(2)))
+            .ok_or(RootFindingError::Overflow)?;
+        let mid = a
+            .checked_add(half_width)
+            .ok_or(RootFindingError::Overflow)?;
+        if half_width.checked_abs().ok_or(RootFindingError::Overflow)? <= tolerance {
+            return Ok(mid);
+        }
+        let fmid = evaluate(&f, mid)?;
+        if fmid.is_zero() {
+            return Ok(mid);
+        }
+        if same_sign(fa, fmid) {
+            a = mid;
+            fa = fmid;
+        } else {
+            b = mid;
+        }
+    }
+    Err(RootFindingError::NoConvergence)
+}
+
+/// Bracket and interpolation state of Brent's method
+struct Brent {
+    a: PreciseDecimal,
+    b: PreciseDecimal,
+    c: PreciseDecimal,
+    fa: PreciseDecimal,
+    fb: PreciseDecimal,
+    fc: PreciseDecimal,
+    d: PreciseDecimal,
+    e: PreciseDecimal,
+}
+
+impl Brent {
+    /// Calculates the next step from b, returning Some(None) once the bracket is narrow enough
+    fn step(&mut self, tolerance: PreciseDecimal) -> Option<Option<PreciseDecimal>> {
+        if same_sign(self.fb, self.fc) {
+            // the root lies between a and b
+            self.c = self.a;
+            self.fc = self.fa;
+            self.d = self.b.checked_sub(self.a)?;
+            self.e = self.d;
+        }
+        if self.fc.checked_abs()? < self.fb.checked_abs()? {
+            // swap b and c so that b is the best approximation
+            self.a = self.b;
+            self.b = self.c;
+            self.c = self.a;
+            self.fa = self.fb;
+            self.fb = self.fc;
+            self.fc = self.fa;
+        }
+        let xm = self.c.checked_sub(self.b)?.checked_div(pdec!(2))?;
+        if xm.checked_abs()? <= tolerance || self.fb.is_zero() {
+            return Some(None);
+        }
+        if self.e.checked_abs()? >= tolerance && self.fa.checked_abs()? > self.fb.checked_abs()? {
+            let (p, q) = self.interpolate(xm)?;
+            let limit = pdec!(3)
+                .checked_mul(xm)?
+                .checked_mul(q)?
+                .checked_sub(tolerance.checked_mul(q)?.checked_abs()?)?
+                .min(self.e.checked_mul(q)?.checked_abs()?);
+            if pdec!(2).checked_mul(p)? < limit {
+                // accept the interpolation
+                self.e = self.d;
+                self.d = p.checked_div(q)?;
+            } else {
+                // the interpolation converges too slowly, bisect
+                self.d = xm;
+                self.e = xm;
+            }
+        } else {
+            // the bracket shrinks too slowly, bisect
+            self.d = xm;
+            self.e = xm;
+        }
+        self.a = self.b;
+        self.fa = self.fb;
+        if self.d.checked_abs()? > tolerance {
+            return Some(Some(self.d));
+        }
+        if xm.is_negative() {
+            return Some(Some(tolerance.checked_neg()?));
+        }
+        Some(Some(tolerance))
+    }
+
+    /// Secant (a = c) or inverse quadratic interpolation step p/q with p >= 0
+    fn interpolate(&self, xm: PreciseDecimal) -> Option<(PreciseDecimal, PreciseDecimal)> {
+        let one = PreciseDecimal::ONE;
+        let s = self.fb.checked_div(self.fa)?;
+        let (p, q) = if self.a == self.c {
+            (
+                pdec!(2).checked_mul(xm)?.checked_mul(s)?,
+                one.checked_sub(s)?,
+            )
+        } else {
+            let q = self.fa.checked_div(self.fc)?;
+            let r = self.fb.checked_div(self.fc)?;
+            let p = pdec!(2)
+                .checked_mul(xm)?
+                .checked_mul(q)?
+                .checked_mul(q.checked_sub(r)?)?
+                .checked_sub(
+                    self.b
+                        .checked_sub(self.a)?
+                        .checked_mul(r.checked_sub(one)?)?,
+                )?
+                .checked_mul(s)?;
+            let q = q
+                .checked_sub(one)?
+                .checked_mul(r.checked_sub(one)?)?
+                .checked_mul(s.checked_sub(one)?)?;
+            (p, q)
+        };
+        if p.is_positive() {
+            return Some((p, q.checked_neg()?));
+        }
+        Some((p.checked_neg()?, q))
+    }
+}
+
+/// Solves f(x) = 0 with Brent's method within the bracket [`lower`, `upper`]
+pub fn brent(
+    f: impl Fn(PreciseDecimal) -> Option<PreciseDecimal>,
+    lower: PreciseDecimal,
+    upper: PreciseDecimal,
+    tolerance: PreciseDecimal,
+    max_iterations: u32,
+) -> Result<PreciseDecimal, RootFindingError> {
+    let fa = evaluate(&f, lower)?;
+    let fb = evaluate(&f, upper)?;
+    if fa.is_zero() {
+        return Ok(lower);
+    }
+    if fb.is_zero() {
+        return Ok(upper);
+    }
+    if same_sign(fa, fb) {
+        // special case (2)
+        return Err(RootFindingError::NoSignChange);
+    }
+
+    let width = upper.checked_sub(lower).ok_or(RootFindingError::Overflow)?;
+    let mut brent = Brent {
+        a: lower,
+        b: upper,
+        c: lower,
+        fa,
+        fb,
+        fc: fa,
+        d: width,
+        e: width,
+    };
+    for _ in 0..max_iterations {
+        match brent.step(tolerance).ok_or(RootFindingError::Overflow)? {
+            None => return Ok(brent.b),
+            Some(step) => {
+                brent.b = brent
+                    .b
+                    .checked_add(step)
+                    .ok_or(RootFindingError::Overflow)?;
+                brent.fb = evaluate(&f, brent.b)?;
+            }
+        }
+    }
+    Err(RootFindingError::NoConvergence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exponential::ExponentialPreciseDecimal;
+    use crate::logarithm::LogarithmPreciseDecimal;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    const SQRT2: PreciseDecimal = pdec!("1.414213562373095048801688724209698078");
+    const TOLERANCE: PreciseDecimal = pdec!("0.000000000000000000000000000001");
+
+    fn square_minus_two(x: PreciseDecimal) -> Option<PreciseDecimal> {
+        x.checked_mul(x)?.checked_sub(pdec!(2))
+    }
+
+    fn assert_close(result: PreciseDecimal, target: PreciseDecimal, max_error: PreciseDecimal) {
+        let error = (result - target).checked_abs().unwrap();
+        assert!(error <= max_error, "{result} != {target}");
+    }
+
+    #[test]
+    fn test_newton_sqrt2() {
+        let root = newton(
+            square_minus_two,
+            |x| Some(x * pdec!(2)),
+            pdec!(1),
+            TOLERANCE,
+            20,
+        );
+        assert_close(
+            root.unwrap(),
+            SQRT2,
+            pdec!("0.000000000000000000000000000000000002"),
+        );
+    }
+
+    #[test]
+    fn test_halley_sqrt2() {
+        let root = halley(
+            square_minus_two,
+            |x| Some(x * pdec!(2)),
+            |_| Some(pdec!(2)),
+            pdec!(1),
+            TOLERANCE,
+            20,
+        );
+        assert_close(
+            root.unwrap(),
+            SQRT2,
+            pdec!("0.000000000000000000000000000000000002"),
+        );
+    }
+
+    #[test]
+    fn test_bisection_sqrt2() {
+        let root = bisection(square_minus_two, pdec!(0), pdec!(2), TOLERANCE, 200);
+        assert_close(root.unwrap(), SQRT2, TOLERANCE);
+    }
+
+    #[test]
+    fn test_brent_sqrt2() {
+        let root = brent(square_minus_two, pdec!(0), pdec!(2), TOLERANCE, 15);
+        assert_close(root.unwrap(), SQRT2, TOLERANCE);
+    }
+
+    #[test_case(pdec!(1), pdec!(3); "bracket")]
+    #[test_case(pdec!(3), pdec!(1); "reversed bracket")]
+    #[test_case(pdec!(2), pdec!(1000); "wide bracket")]
+    fn test_brent_cubic(lower: PreciseDecimal, upper: PreciseDecimal) {
+        // x^3 - 2x - 5 = 0, Wallis' example
+        let f = |x: PreciseDecimal| {
+            x.checked_mul(x)?
+                .checked_mul(x)?
+                .checked_sub(x * pdec!(2) + pdec!(5))
+        };
+        let target = pdec!("2.094551481542326591482386540579302963");
+        assert_close(
+            brent(f, lower, upper, TOLERANCE, 20).unwrap(),
+            target,
+            TOLERANCE,
+        );
+        assert_close(
+            bisection(f, lower, upper, TOLERANCE, 200).unwrap(),
+            target,
+            TOLERANCE,
+        );
+    }
+
+    #[test]
+    fn test_solvers_exp() {
+        // e^x = 3, the error is dominated by the error of exp
+        let f = |x: PreciseDecimal| x.exp()?.checked_sub(pdec!(3));
+        let df = |x: PreciseDecimal| x.exp();
+        let target = pdec!(3).ln().unwrap();
+        let max_error = pdec!("0.00000000000000001");
+        let tolerance = pdec!("0.000000000000000001");
+        assert_close(
+            newton(f, df, pdec!(0), tolerance, 50).unwrap(),
+            target,
+            max_error,
+        );
+        assert_close(
+            halley(f, df, df, pdec!(0), tolerance, 50).unwrap(),
+            target,
+            max_error,
+        );
+        assert_close(
+            bisection(f, pdec!(0), pdec!(5), tolerance, 100).unwrap(),
+            target,
+            max_error,
+        );
+        assert_close(
+            brent(f, pdec!(0), pdec!(5), tolerance, 100).unwrap(),
+            target,
+            max_error,
+        );
+    }
+
+    #[test]
+    fn test_exact_root() {
+        let f = |x: PreciseDecimal| x.checked_sub(pdec!(1));
+        assert_eq!(
+            newton(f, |_| Some(pdec!(1)), pdec!(1), TOLERANCE, 1),
+            Ok(pdec!(1))
+        );
+        assert_eq!(bisection(f, pdec!(1), pdec!(5), TOLERANCE, 1), Ok(pdec!(1)));
+        assert_eq!(brent(f, pdec!(0), pdec!(1), TOLERANCE, 1), Ok(pdec!(1)));
+    }
+
+    #[test]
+    fn test_no_sign_change() {
+        let f = |x: PreciseDecimal| x.checked_mul(x)?.checked_add(pdec!(1));
+        assert_eq!(
+            bisection(f, pdec!(-1), pdec!(1), TOLERANCE, 100),
+            Err(RootFindingError::NoSignChange)
+        );
+        assert_eq!(
+            brent(f, pdec!(-1), pdec!(1), TOLERANCE, 100),
+            Err(RootFindingError::NoSignChange)
+        );
+    }
+
+    #[test]
+    fn test_zero_derivative() {
+        let f = |x: PreciseDecimal| x.checked_mul(x)?.checked_add(pdec!(1));
+        let df = |x: PreciseDecimal| x.checked_mul(pdec!(2));
+        assert_eq!(
+            newton(f, df, pdec!(0), TOLERANCE, 100),
+            Err(RootFindingError::ZeroDerivative)
+        );
+    }
+
+    #[test]
+    fn test_evaluation_failed() {
+        let f = |x: PreciseDecimal| x.ln();
+        let df = |x: PreciseDecimal| PreciseDecimal::ONE.checked_div(x);
+        // the first Newton step from 5 overshoots into the negative domain of ln
+        assert_eq!(
+            newton(f, df, pdec!(5), TOLERANCE, 100),
+            Err(RootFindingError::EvaluationFailed)
+        );
+        assert_eq!(
+            bisection(f, pdec!(-1), pdec!(2), TOLERANCE, 100),
+            Err(RootFindingError::EvaluationFailed)
+        );
+    }
+
+    #[test]
+    fn test_no_convergence() {
+        assert_eq!(
+            newton(
+                square_minus_two,
+                |x| Some(x * pdec!(2)),
+                pdec!(1),
+                TOLERANCE,
+                2
+            ),
+            Err(RootFindingError::NoConvergence)
+        );
+        assert_eq!(
+            bisection(square_minus_two, pdec!(0), pdec!(2), TOLERANCE, 10),
+            Err(RootFindingError::NoConvergence)
+        );
+        assert_eq!(
+            brent(square_minus_two, pdec!(0), pdec!(2), TOLERANCE, 2),
+            Err(RootFindingError::NoConvergence)
+        );
+    }
+}
@@ -0,0 +1,624 @@
+// Descriptive statistics over slices of Decimals and streaming accumulators
+//
+// Method:
+//      All intermediate results are calculated with PreciseDecimal, so sums of Decimals cannot overflow
+//      and every division keeps 36 decimals before the result is truncated to a Decimal.
+//
+//      1. Means:
+//              arithmetic = Σ x_i / n
+//              geometric  = e^(Σ ln(x_i) / n)
+//              harmonic   = n / Σ (1/x_i)
+//              weighted   = Σ w_i*x_i / Σ w_i
+//
+//      2. Variance with Welford's algorithm, updating the mean and the sum of squared deviations M2:
+//              δ = x_k - mean_k-1
+//              mean_k = mean_k-1 + δ/k
+//              M2_k = M2_k-1 + δ*(x_k - mean_k)
+//         population variance = M2/n, sample variance = M2/(n-1), standard deviation = sqrt(variance)
+//
+//      3. Median of the sorted values, the mean of the two middle values for an even count.
+//         The weighted median is the smallest value whose cumulative weight reaches half of the total.
+//
+//      4. RunningStatistics applies the Welford update one observation at a time and additionally keeps
+//         the minimum and maximum, so it can be stored in component state and queried in O(1).
+//
+//      5. EwmaStatistics weights observations in time with the decay factor f = e^(-dt/tau):
+//              δ = x - mean
+//              mean' = x - δ*f
+//              var'  = f * (var + (1-f)*δ^2)
+//
+// Special cases:
+//      1. Empty slices and slices of different lengths return None.
+//      2. Geometric and harmonic means require positive values.
+//      3. Weights must not be negative and their sum must be positive.
+//      4. The sample variance requires at least two values.
+//      5. Observations older than the latest EwmaStatistics update return None, dt == 0 leaves it unchanged.
+//
+// Accuracy:
+//      The geometric mean has the relative error of exp (2^-59) plus the error of ln (2^-58.45).
+//      All other statistics are exact up to the truncation of PreciseDecimal operations.
+//      The decay factor of EwmaStatistics has the error of exp for non-positive arguments (2^-59).
+
+use crate::decay::decay_factor;
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::logarithm::LogarithmPreciseDecimal;
+use radix_common::math::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Decimal, PreciseDecimal};
+use radix_common::ScryptoSbor;
+
+/// Converts a PreciseDecimal result back to Decimal
+fn to_decimal(value: PreciseDecimal) -> Option<Decimal> {
+    value.try_into().ok()
+}
+
+/// Feeds a slice of Decimals into a RunningStatistics accumulator
+fn running_statistics(values: &[Decimal]) -> Option<RunningStatistics> {
+    let mut statistics = RunningStatistics::new();
+    for value in values {
+        statistics.update(PreciseDecimal::from(*value))?;
+    }
+    Some(statistics)
+}
+
+/// Calculates the arithmetic mean of a slice of Decimals
+pub fn mean(values: &[Decimal]) -> Option<Decimal> {
+    if values.is_empty() {
+        return None;
+    }
+    let sum = values.iter().try_fold(PreciseDecimal::ZERO, |sum, value| {
+        sum.checked_add(PreciseDecimal::from(*value))
+    })?;
+    to_decimal(sum.checked_div(PreciseDecimal::from(values.len() as u64))?)
+}
+
+/// Calculates the geometric mean of a slice of positive Decimals
+pub fn geometric_mean(values: &[Decimal]) -> Option<Decimal> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sum_ln = PreciseDecimal::ZERO;
+    for value in values {
+        // ln returns None for non-positive values, special case (2)
+        sum_ln = sum_ln.checked_add(PreciseDecimal::from(*value).ln()?)?;
+    }
+    let mean_ln = sum_ln.checked_div(PreciseDecimal::from(values.len() as u64))?;
+    to_decimal(mean_ln.exp()?)
+}
+
+/// Calculates the harmonic mean of a slice of positive Decimals
+pub fn harmonic_mean(values: &[Decimal]) -> Option<Decimal> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sum_inverse = PreciseDecimal::ZERO;
+    for value in values {
+        if !value.is_positive() {
+            // special case (2)
+            return None;
+        }
+        sum_inverse = sum_inverse
+            .checked_add(PreciseDecimal::ONE.checked_div(PreciseDecimal::from(*value))?)?;
+    }
+    to_decimal(PreciseDecimal::from(values.len() as u64).checked_div(sum_inverse)?)
+}
+
+/// Calculates the mean of a slice of Decimals weighted with non-negative `weights`
+pub fn weighted_mean(values: &[Decimal], weights: &[Decimal]) -> Option<Decimal> {
+    if values.is_empty() || values.len() != weights.len() {
+        return None;
+    }
+    let mut sum = PreciseDecimal::ZERO;
+    let mut total_weight = PreciseDecimal::ZERO;
+    for (value, weight) in values.iter().zip(weights) {
+        if weight.is_negative() {
+            // special case (3)
+            return None;
+        }
+        let weight = PreciseDecimal::from(*weight);
+        sum = sum.checked_add(PreciseDecimal::from(*value).checked_mul(weight)?)?;
+        total_weight = total_weight.checked_add(weight)?;
+    }
+    to_decimal(sum.checked_div(total_weight)?)
+}
+
+/// Calculates the population variance of a slice of Decimals
+pub fn variance(values: &[Decimal]) -> Option<Decimal> {
+    to_decimal(running_statistics(values)?.variance()?)
+}
+
+/// Calculates the sample variance of a slice of Decimals
+pub fn sample_variance(values: &[Decimal]) -> Option<Decimal> {
+    to_decimal(running_statistics(values)?.sample_variance()?)
+}
+
+/// Calculates the population standard deviation of a slice of Decimals
+pub fn standard_deviation(values: &[Decimal]) -> Option<Decimal> {
+    to_decimal(running_statistics(values)?.standard_deviation()?)
+}
+
+/// Calculates the sample standard deviation of a slice of Decimals
+pub fn sample_standard_deviation(values: &[Decimal]) -> Option<Decimal> {
+    to_decimal(running_statistics(values)?.sample_standard_deviation()?)
+}
+
+/// Calculates the median of a slice of Decimals
+pub fn median(values: &[Decimal]) -> Option<Decimal> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort();
+    let middle = sorted.len() / 2;
+    let upper = *sorted.get(middle)?;
+    if sorted.len() % 2 == 1 {
+        return Some(upper);
+    }
+    let lower = *sorted.get(middle.checked_sub(1)?)?;
+    let sum = PreciseDecimal::from(lower).checked_add(PreciseDecimal::from(upper))?;
+    to_decimal(sum.checked_div(PreciseDecimal::from(2))?)
+}
+
+/// Calculates the lower weighted median of a slice of Decimals with non-negative `weights`
+pub fn weighted_median(values: &[Decimal], weights: &[Decimal]) -> Option<Decimal> {
+    if values.is_empty() || values.len() != weights.len() {
+        return None;
+    }
+    if weights.iter().any(|weight| weight.is_negative()) {
+        // special case (3)
+        return None;
+    }
+    let mut pairs: Vec<(Decimal, PreciseDecimal)> = values
+        .iter()
+        .zip(weights)
+        .map(|(value, weight)| (*value, PreciseDecimal::from(*weight)))
+        .collect();
+    pairs.sort_by_key(|(value, _)| *value);
+    let total_weight = pairs
+        .iter()
+        .try_fold(PreciseDecimal::ZERO, |sum, (_, weight)| {
+            sum.checked_add(*weight)
+        })?;
+    if !total_weight.is_positive() {
+        // special case (3)
+        return None;
+    }
+    // the cumulative weight reaches half of the total, compared as 2 * cumulative >= total
+    let mut cumulative = PreciseDecimal::ZERO;
+    for (value, weight) in pairs {
+        cumulative = cumulative.checked_add(weight)?;
+        if cumulative.checked_mul(PreciseDecimal::from(2))? >= total_weight {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Streaming accumulator of count, mean, M2 (Welford's algorithm), minimum and maximum
+#[derive(ScryptoSbor, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RunningStatistics {
+    count: u64,
+    mean: PreciseDecimal,
+    m2: PreciseDecimal,
+    min: PreciseDecimal,
+    max: PreciseDecimal,
+}
+
+impl RunningStatistics {
+    /// Creates an empty accumulator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an observation
+    ///
+    /// Returns None and leaves the accumulator unchanged if an intermediate result overflows.
+    pub fn update(&mut self, value: PreciseDecimal) -> Option<()> {
+        let count = self.count.checked_add(1)?;
+        let delta = value.checked_sub(self.mean)?;
+        let mean = self
+            .mean
+            .checked_add(delta.checked_div(PreciseDecimal::from(count))?)?;
+        let m2 = self
+            .m2
+            .checked_add(delta.checked_mul(value.checked_sub(mean)?)?)?;
+        let (min, max) = if self.count == 0 {
+            (value, value)
+        } else {
+            (self.min.min(value), self.max.max(value))
+        };
+        *self = Self {
+            count,
+            mean,
+            m2,
+            min,
+            max,
+        };
+        Some(())
+    }
+
+    /// Number of observations
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean of all observations, None if empty
+    pub fn mean(&self) -> Option<PreciseDecimal> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Sum of squared deviations from the mean
+    pub fn m2(&self) -> PreciseDecimal {
+        self.m2
+    }
+
+    /// Smallest observation, None if empty
+    pub fn min(&self) -> Option<PreciseDecimal> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Largest observation, None if empty
+    pub fn max(&self) -> Option<PreciseDecimal> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Population variance M2/n, None if empty
+    pub fn variance(&self) -> Option<PreciseDecimal> {
+        if self.count == 0 {
+            return None;
+        }
+        self.m2.checked_div(PreciseDecimal::from(self.count))
+    }
+
+    /// Sample variance M2/(n-1), None for less than two observations
+    pub fn sample_variance(&self) -> Option<PreciseDecimal> {
+        if self.count < 2 {
+            // special case (4)
+            return None;
+        }
+        self.m2
+            .checked_div(PreciseDecimal::from(self.count.checked_sub(1)?))
+    }
+
+    /// Population standard deviation, None if empty
+    pub fn standard_deviation(&self) -> Option<PreciseDecimal> {
+        self.variance()?.checked_sqrt()
+    }
+
+    /// Sample standard deviation, None for less than two observations
+    pub fn sample_standard_deviation(&self) -> Option<PreciseDecimal> {
+        self.sample_variance()?.checked_sqrt()
+    }
+}
+
+/// Exponentially weighted moving mean and variance of observations in time with time constant `tau`
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EwmaStatistics {
+    tau: PreciseDecimal,
+    timestamp: i64,
+    mean: PreciseDecimal,
+    variance: PreciseDecimal,
+}
+
+impl EwmaStatistics {
+    /// Creates a new accumulator with the first observation `value` at `timestamp`
+    ///
+    /// Returns None if `tau` is not positive.
+    pub fn new(timestamp: i64, value: PreciseDecimal, tau: PreciseDecimal) -> Option<Self> {
+        if !tau.is_positive() {
+            return None;
+        }
+        Some(Self {
+            tau,
+            timestamp,
+            mean: value,
+            variance: PreciseDecimal::ZERO,
+        })
+    }
+
+    /// Adds the observation `value` at `timestamp`, weighted with `1 - e^(-dt/tau)`
+    ///
+    /// Returns None and leaves the accumulator unchanged if `timestamp` is older than the latest update.
+    pub fn update(&mut self, timestamp: i64, value: PreciseDecimal) -> Option<()> {
+        let dt = PreciseDecimal::from(timestamp.checked_sub(self.timestamp)?);
+        let factor = decay_factor(dt, self.tau)?;
+        let weight = PreciseDecimal::ONE.checked_sub(factor)?;
+        let delta = value.checked_sub(self.mean)?;
+        let mean = value.checked_sub(delta.checked_mul(factor)?)?;
+        let variance = factor.checked_mul(
+            self.variance
+                .checked_add(weight.checked_mul(delta.checked_mul(delta)?)?)?,
+        )?;
+        *self = Self {
+            tau: self.tau,
+            timestamp,
+            mean,
+            variance,
+        };
+        Some(())
+    }
+
+    /// Time constant tau
+    pub fn tau(&self) -> PreciseDecimal {
+        self.tau
+    }
+
+    /// Timestamp of the latest update
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Exponentially weighted moving mean
+    pub fn mean(&self) -> PreciseDecimal {
+        self.mean
+    }
+
+    /// Exponentially weighted moving variance
+    pub fn variance(&self) -> PreciseDecimal {
+        self.variance
+    }
+
+    /// Exponentially weighted moving standard deviation
+    pub fn standard_deviation(&self) -> Option<PreciseDecimal> {
+        self.variance.checked_sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::ApproxDecimal;
+    use pretty_assertions::assert_eq;
+    use radix_common::data::scrypto::{scrypto_decode, scrypto_encode};
+    use radix_common_derive::{dec, pdec};
+    use test_case::test_case;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(geometric_mean(&[]), None);
+        assert_eq!(harmonic_mean(&[]), None);
+        assert_eq!(weighted_mean(&[], &[]), None);
+        assert_eq!(variance(&[]), None);
+        assert_eq!(sample_variance(&[dec!(1)]), None);
+        assert_eq!(median(&[]), None);
+        assert_eq!(weighted_median(&[], &[]), None);
+    }
+
+    #[test]
+    fn test_mean() {
+        assert_eq!(
+            mean(&[dec!(1), dec!(2), dec!(3), dec!(4)]),
+            Some(dec!("2.5"))
+        );
+        assert_eq!(
+            mean(&[dec!(1), dec!(2), dec!(2)]),
+            Some(dec!("1.666666666666666666"))
+        );
+        assert_eq!(mean(&[dec!(-5), dec!(5)]), Some(dec!(0)));
+        // the sum overflows Decimal but not PreciseDecimal
+        assert_eq!(mean(&[Decimal::MAX, Decimal::MAX]), Some(Decimal::MAX));
+    }
+
+    #[test]
+    fn test_geometric_mean() {
+        assert_eq!(geometric_mean(&[dec!(1), dec!(0), dec!(2)]), None);
+        assert_eq!(geometric_mean(&[dec!(-1), dec!(2)]), None);
+        let result = geometric_mean(&[dec!(2), dec!(8)]).unwrap();
+        assert!(result.approx_eq(dec!(4), 1));
+        let result = geometric_mean(&[dec!(1), dec!(3), dec!(9), dec!(27)]).unwrap();
+        // sqrt(27) = 5.196152422706631880582339024517617100
+        assert!(
+            (result - dec!("5.196152422706631880"))
+                .checked_abs()
+                .unwrap()
+                <= dec!("0.000000000000000001")
+        );
+    }
+
+    #[test]
+    fn test_harmonic_mean() {
+        assert_eq!(harmonic_mean(&[dec!(1), dec!(0)]), None);
+        assert_eq!(harmonic_mean(&[dec!(1), dec!(-1)]), None);
+        assert_eq!(harmonic_mean(&[dec!(1), dec!(4), dec!(4)]), Some(dec!(2)));
+        assert_eq!(harmonic_mean(&[dec!(40), dec!(60)]), Some(dec!(48)));
+    }
+
+    #[test]
+    fn test_weighted_mean() {
+        assert_eq!(weighted_mean(&[dec!(1), dec!(2)], &[dec!(1)]), None);
+        assert_eq!(
+            weighted_mean(&[dec!(1), dec!(2)], &[dec!(1), dec!(-1)]),
+            None
+        );
+        assert_eq!(
+            weighted_mean(&[dec!(1), dec!(2)], &[dec!(0), dec!(0)]),
+            None
+        );
+        assert_eq!(
+            weighted_mean(&[dec!(1), dec!(2)], &[dec!(3), dec!(1)]),
+            Some(dec!("1.25"))
+        );
+        assert_eq!(
+            weighted_mean(
+                &[dec!(10), dec!(20), dec!(30)],
+                &[dec!(0), dec!(1), dec!(0)]
+            ),
+            Some(dec!(20))
+        );
+    }
+
+    #[test_case(&[dec!(5)], dec!(0), None; "single value")]
+    #[test_case(&[dec!(2), dec!(4), dec!(4), dec!(4), dec!(5), dec!(5), dec!(7), dec!(9)], dec!(4), Some(dec!("4.571428571428571428")); "textbook")]
+    #[test_case(&[dec!(1000000000.1), dec!(1000000000.2), dec!(1000000000.3)], dec!("0.006666666666666666"), Some(dec!("0.01")); "large offset")]
+    fn test_variance(values: &[Decimal], population: Decimal, sample: Option<Decimal>) {
+        assert_eq!(variance(values), Some(population));
+        assert_eq!(sample_variance(values), sample);
+    }
+
+    #[test]
+    fn test_standard_deviation() {
+        let values = [
+            dec!(2),
+            dec!(4),
+            dec!(4),
+            dec!(4),
+            dec!(5),
+            dec!(5),
+            dec!(7),
+            dec!(9),
+        ];
+        assert_eq!(standard_deviation(&values), Some(dec!(2)));
+        // sqrt(32/7) = 2.138089935299395077476427847038028172
+        assert_eq!(
+            sample_standard_deviation(&values),
+            Some(dec!("2.138089935299395077"))
+        );
+        assert_eq!(sample_standard_deviation(&values[..1]), None);
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[dec!(3), dec!(1), dec!(2)]), Some(dec!(2)));
+        assert_eq!(
+            median(&[dec!(4), dec!(1), dec!(3), dec!(2)]),
+            Some(dec!("2.5"))
+        );
+        assert_eq!(median(&[dec!(7)]), Some(dec!(7)));
+        assert_eq!(median(&[Decimal::MAX, Decimal::MAX]), Some(Decimal::MAX));
+    }
+
+    #[test]
+    fn test_weighted_median() {
+        assert_eq!(weighted_median(&[dec!(1), dec!(2)], &[dec!(1)]), None);
+        assert_eq!(
+            weighted_median(&[dec!(1), dec!(2)], &[dec!(1), dec!(-1)]),
+            None
+        );
+        assert_eq!(
+            weighted_median(&[dec!(1), dec!(2)], &[dec!(0), dec!(0)]),
+            None
+        );
+        assert_eq!(
+            weighted_median(&[dec!(3), dec!(1), dec!(2)], &[dec!(1), dec!(1), dec!(1)]),
+            Some(dec!(2))
+        );
+        assert_eq!(
+            weighted_median(
+                &[dec!(1), dec!(2), dec!(3), dec!(4)],
+                &[dec!("0.1"), dec!("0.2"), dec!("0.3"), dec!("0.4")]
+            ),
+            Some(dec!(3))
+        );
+        // the lower median is returned if the cumulative weight hits exactly half
+        assert_eq!(
+            weighted_median(&[dec!(1), dec!(2)], &[dec!(1), dec!(1)]),
+            Some(dec!(1))
+        );
+    }
+
+    #[test]
+    fn test_running_statistics_empty() {
+        let statistics = RunningStatistics::new();
+        assert_eq!(statistics.count(), 0);
+        assert_eq!(statistics.mean(), None);
+        assert_eq!(statistics.min(), None);
+        assert_eq!(statistics.max(), None);
+        assert_eq!(statistics.variance(), None);
+        assert_eq!(statistics.sample_variance(), None);
+    }
+
+    #[test]
+    fn test_running_statistics() {
+        let mut statistics = RunningStatistics::new();
+        for value in [2, 4, 4, 4, 5, 5, 7, 9] {
+            statistics.update(PreciseDecimal::from(value)).unwrap();
+        }
+        assert_eq!(statistics.count(), 8);
+        assert_eq!(statistics.min(), Some(pdec!(2)));
+        assert_eq!(statistics.max(), Some(pdec!(9)));
+        // the truncated intermediate means add a few ulps per observation to M2
+        let max_error = pdec!("0.0000000000000000000000000000000001");
+        for (result, target) in [
+            (statistics.mean().unwrap(), pdec!(5)),
+            (statistics.m2(), pdec!(32)),
+            (statistics.variance().unwrap(), pdec!(4)),
+            (statistics.standard_deviation().unwrap(), pdec!(2)),
+            (
+                statistics.sample_variance().unwrap(),
+                pdec!("4.571428571428571428571428571428571428"),
+            ),
+        ] {
+            assert!((result - target).checked_abs().unwrap() <= max_error);
+        }
+        // sqrt(32/7) = 2.138089935299395077476427847038028172
+        let error = (statistics.sample_standard_deviation().unwrap()
+            - pdec!("2.138089935299395077476427847038028172"))
+        .checked_abs()
+        .unwrap();
+        assert!(error <= max_error);
+    }
+
+    #[test]
+    fn test_running_statistics_overflow() {
+        let mut statistics = RunningStatistics::new();
+        statistics.update(PreciseDecimal::MAX).unwrap();
+        let before = statistics;
+        assert_eq!(statistics.update(PreciseDecimal::MIN), None);
+        assert_eq!(statistics, before);
+    }
+
+    #[test]
+    fn test_ewma_statistics_invalid() {
+        assert_eq!(EwmaStatistics::new(0, pdec!(1), pdec!(0)), None);
+        assert_eq!(EwmaStatistics::new(0, pdec!(1), pdec!(-60)), None);
+        let mut statistics = EwmaStatistics::new(100, pdec!(1), pdec!(60)).unwrap();
+        assert_eq!(statistics.update(99, pdec!(2)), None);
+        assert_eq!(statistics.timestamp(), 100);
+    }
+
+    #[test]
+    fn test_ewma_statistics() {
+        let mut statistics = EwmaStatistics::new(0, pdec!(10), pdec!(60)).unwrap();
+        assert_eq!(statistics.tau(), pdec!(60));
+        assert_eq!(statistics.variance(), pdec!(0));
+        statistics.update(0, pdec!(20)).unwrap();
+        assert_eq!(statistics.mean(), pdec!(10));
+
+        // f = e^-1 = 0.367879441171442321595523770161460867
+        statistics.update(60, pdec!(20)).unwrap();
+        assert_eq!(statistics.timestamp(), 60);
+        let f = pdec!(-1).exp().unwrap();
+        assert_eq!(statistics.mean(), pdec!(20) - pdec!(10) * f);
+        // f * (1 - f) * 100 = 23.254415793482962970152427518897646403
+        let error = (statistics.variance() - pdec!("23.254415793482962970152427518897646403"))
+            .checked_abs()
+            .unwrap();
+        // error of exp times the derivative 100 * (1 - 2f)
+        assert!(error <= pdec!("0.00000000000000005"));
+    }
+
+    #[test]
+    fn test_ewma_statistics_fully_decayed() {
+        let mut statistics = EwmaStatistics::new(0, pdec!(10), pdec!(1)).unwrap();
+        statistics.update(1000, pdec!(20)).unwrap();
+        assert_eq!(statistics.mean(), pdec!(20));
+        assert_eq!(statistics.variance(), pdec!(0));
+        assert_eq!(statistics.standard_deviation(), Some(pdec!(0)));
+    }
+
+    #[test]
+    fn test_sbor_roundtrip() {
+        let mut running = RunningStatistics::new();
+        running.update(pdec!("1.5")).unwrap();
+        running.update(pdec!("-2.5")).unwrap();
+        let encoded = scrypto_encode(&running).unwrap();
+        assert_eq!(
+            scrypto_decode::<RunningStatistics>(&encoded).unwrap(),
+            running
+        );
+
+        let mut ewma = EwmaStatistics::new(0, pdec!(3), pdec!(60)).unwrap();
+        ewma.update(30, pdec!(4)).unwrap();
+        let encoded = scrypto_encode(&ewma).unwrap();
+        assert_eq!(scrypto_decode::<EwmaStatistics>(&encoded).unwrap(), ewma);
+    }
+}
@@ -0,0 +1,441 @@
+// Time-weighted average price (TWAP) accumulator
+//
+// Method:
+//      1. Accumulation: for every price change the previous price is weighted with the elapsed time
+//              acc_price    += price * dt
+//              acc_ln_price += ln(price) * dt
+//         and stored as an observation (timestamp, acc_price, acc_ln_price) in a ring buffer.
+//
+//      2. Interpolation: the price is constant between two observations, therefore the accumulators
+//         of any timestamp in between are interpolated linearly with a single final division:
+//              acc(t) = acc(t_i) + (acc(t_i+1) - acc(t_i)) * (t - t_i) / (t_i+1 - t_i)
+//         After the latest observation the current price is extrapolated:
+//              acc(t) = acc(t_n) + price_n * (t - t_n)
+//
+//      3. TWAP over [t_0, t_1]:
+//              arithmetic = (acc_price(t_1) - acc_price(t_0)) / (t_1 - t_0)
+//              geometric  = exp((acc_ln_price(t_1) - acc_ln_price(t_0)) / (t_1 - t_0))
+//
+// Accuracy:
+//      Timestamps are integers, so price * dt and ln(price) * dt are exact and every difference of
+//      consecutive accumulators is a multiple of dt. The multiplication (t - t_i) * Δacc is exact and the
+//      division by Δt has no remainder, which makes interpolated and extrapolated accumulators exact.
+//      The arithmetic TWAP is therefore exact up to the truncation of the final division.
+//      The mean of the logarithms is a weighted average of `ln` results, so its error is bound by the
+//      error of `ln` itself (2^-58.45). The geometric TWAP therefore has the same error as `pow` with
+//      exponent one:
+//              error_geometric = error_exp(mean_ln) + e^mean_ln * error_ln
+
+use crate::error_estimation::{error_exp, error_ln};
+use crate::exponential::ExponentialPreciseDecimal;
+use crate::logarithm::LogarithmPreciseDecimal;
+use radix_common::math::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, PreciseDecimal};
+use radix_common::ScryptoSbor;
+
+/// Accumulated prices at a specific point in time
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Observation {
+    /// Seconds since unix epoch
+    pub timestamp: i64,
+    /// Sum of `price * dt`
+    pub price_cumulative: PreciseDecimal,
+    /// Sum of `ln(price) * dt`
+    pub ln_price_cumulative: PreciseDecimal,
+}
+
+/// Time-weighted average price together with its maximum approximation error
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Twap {
+    pub value: PreciseDecimal,
+    pub max_error: PreciseDecimal,
+}
+
+/// Accumulator for arithmetic and geometric TWAPs storing a fixed number of observations in a ring buffer
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct TwapAccumulator {
+    observations: Vec<Observation>,
+    capacity: u32,
+    newest: u32,
+    price: PreciseDecimal,
+    ln_price: PreciseDecimal,
+}
+
+impl Observation {
+    /// Extrapolates the accumulators from this observation with a constant price
+    fn accumulate(
+        &self,
+        timestamp: i64,
+        price: PreciseDecimal,
+        ln_price: PreciseDecimal,
+    ) -> Option<Observation> {
+        let dt = PreciseDecimal::from(timestamp.checked_sub(self.timestamp)?);
+        Some(Observation {
+            timestamp,
+            price_cumulative: self.price_cumulative.checked_add(price.checked_mul(dt)?)?,
+            ln_price_cumulative: self
+                .ln_price_cumulative
+                .checked_add(ln_price.checked_mul(dt)?)?,
+        })
+    }
+}
+
+impl TwapAccumulator {
+    /// Creates a new accumulator with the initial price at `timestamp` and room for `capacity` observations
+    pub fn new(timestamp: i64, price: PreciseDecimal, capacity: u32) -> Option<Self> {
+        if capacity == 0 {
+            return None;
+        }
+        Some(Self {
+            observations: vec![Observation {
+                timestamp,
+                price_cumulative: PreciseDecimal::ZERO,
+                ln_price_cumulative: PreciseDecimal::ZERO,
+            }],
+            capacity,
+            newest: 0,
+            price,
+            ln_price: price.ln()?,
+        })
+    }
+
+    /// Records a new price at `timestamp`, overwriting the oldest observation once the capacity is reached
+    ///
+    /// Returns None if `timestamp` is older than the latest observation or `price` is not positive.
+    pub fn update(&mut self, timestamp: i64, price: PreciseDecimal) -> Option<()> {
+        let ln_price = price.ln()?;
+        let latest = self.latest()?;
+        if timestamp < latest.timestamp {
+            return None;
+        }
+        if timestamp > latest.timestamp {
+            let observation = latest.accumulate(timestamp, self.price, self.ln_price)?;
+            if self.observations.len() < self.capacity as usize {
+                let newest = u32::try_from(self.observations.len()).ok()?;
+                self.observations.push(observation);
+                self.newest = newest;
+            } else {
+                let newest = self.newest.checked_add(1)?.checked_rem(self.capacity)?;
+                *self.observations.get_mut(newest as usize)? = observation;
+                self.newest = newest;
+            }
+        }
+        // a price change within the same second replaces the current price
+        self.price = price;
+        self.ln_price = ln_price;
+        Some(())
+    }
+
+    /// Current price
+    pub fn price(&self) -> PreciseDecimal {
+        self.price
+    }
+
+    /// Latest observation, None only for an inconsistent decoded state
+    pub fn latest(&self) -> Option<Observation> {
+        self.observations.get(self.newest as usize).copied()
+    }
+
+    /// Oldest observation still stored, None only for an inconsistent decoded state
+    pub fn oldest(&self) -> Option<Observation> {
+        self.observation(0)
+    }
+
+    /// All stored observations in chronological order, None only for an inconsistent decoded state
+    pub fn observations(&self) -> Option<Vec<Observation>> {
+        (0..self.observations.len())
+            .map(|i| self.observation(i))
+            .collect()
+    }
+
+    /// Returns the i-th stored observation in chronological order
+    fn observation(&self, i: usize) -> Option<Observation> {
+        let len = self.observations.len();
+        let oldest = (self.newest as usize).checked_add(1)?.checked_rem(len)?;
+        let index = oldest.checked_add(i)?.checked_rem(len)?;
+        self.observations.get(index).copied()
+    }
+
+    /// Calculates the accumulators at an arbitrary `timestamp`
+    ///
+    /// Returns None if `timestamp` is older than the oldest stored observation.
+    pub fn observe(&self, timestamp: i64) -> Option<Observation> {
+        let latest = self.latest()?;
+        if timestamp >= latest.timestamp {
+            return latest.accumulate(timestamp, self.price, self.ln_price);
+        }
+        if timestamp < self.oldest()?.timestamp {
+            return None;
+        }
+
+        // binary search for the last observation at or before timestamp
+        let (mut low, mut high) = (0, self.observations.len().checked_sub(1)?);
+        while low < high {
+            let mid = low.checked_add(high)?.div_ceil(2);
+            if self.observation(mid)?.timestamp <= timestamp {
+                low = mid;
+            } else {
+                high = mid.checked_sub(1)?;
+            }
+        }
+        let before = self.observation(low)?;
+        if before.timestamp == timestamp {
+            return Some(before);
+        }
+
+        // (2) interpolation with a single division, exact because Δacc is a multiple of dt
+        let after = self.observation(low.checked_add(1)?)?;
+        let elapsed = PreciseDecimal::from(timestamp.checked_sub(before.timestamp)?);
+        let dt = PreciseDecimal::from(after.timestamp.checked_sub(before.timestamp)?);
+        Some(Observation {
+            timestamp,
+            price_cumulative: interpolate(
+                before.price_cumulative,
+                after.price_cumulative,
+                elapsed,
+                dt,
+            )?,
+            ln_price_cumulative: interpolate(
+                before.ln_price_cumulative,
+                after.ln_price_cumulative,
+                elapsed,
+                dt,
+            )?,
+        })
+    }
+
+    /// Calculates the arithmetic TWAP between `start` and `end`
+    pub fn arithmetic_twap(&self, start: i64, end: i64) -> Option<Twap> {
+        let (from, to, dt) = self.window(start, end)?;
+        let value = to
+            .price_cumulative
+            .checked_sub(from.price_cumulative)?
+            .checked_div(dt)?;
+        Some(Twap {
+            value,
+            max_error: ulp(),
+        })
+    }
+
+    /// Calculates the geometric TWAP between `start` and `end`
+    pub fn geometric_twap(&self, start: i64, end: i64) -> Option<Twap> {
+        let (from, to, dt) = self.window(start, end)?;
+        let mean_ln = to
+            .ln_price_cumulative
+            .checked_sub(from.ln_price_cumulative)?
+            .checked_div(dt)?;
+        let value = mean_ln.exp()?;
+        let max_error = error_exp(mean_ln)?
+            .checked_add(value.checked_mul(error_ln())?)?
+            .checked_add(ulp())?;
+        Some(Twap { value, max_error })
+    }
+
+    fn window(&self, start: i64, end: i64) -> Option<(Observation, Observation, PreciseDecimal)> {
+        if end <= start {
+            return None;
+        }
+        let dt = PreciseDecimal::from(end.checked_sub(start)?);
+        Some((self.observe(start)?, self.observe(end)?, dt))
+    }
+}
+
+/// Interpolates `before + (after - before) * elapsed / dt` with a single final division
+fn interpolate(
+    before: PreciseDecimal,
+    after: PreciseDecimal,
+    elapsed: PreciseDecimal,
+    dt: PreciseDecimal,
+) -> Option<PreciseDecimal> {
+    before.checked_add(
+        after
+            .checked_sub(before)?
+            .checked_mul(elapsed)?
+            .checked_div(dt)?,
+    )
+}
+
+/// Smallest representable PreciseDecimal accounting for the truncation of a single operation
+fn ulp() -> PreciseDecimal {
+    PreciseDecimal::from_precise_subunits(1.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radix_common::data::scrypto::{scrypto_decode, scrypto_encode};
+    use radix_common_derive::pdec;
+    use test_case::test_case;
+
+    fn accumulator() -> TwapAccumulator {
+        // price 2 for 100 seconds, then 8 for 100 seconds, then 4
+        let mut acc = TwapAccumulator::new(1000, pdec!(2), 10).unwrap();
+        acc.update(1100, pdec!(8)).unwrap();
+        acc.update(1200, pdec!(4)).unwrap();
+        acc
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        assert_eq!(TwapAccumulator::new(1000, pdec!(2), 0), None);
+        assert_eq!(TwapAccumulator::new(1000, pdec!(0), 10), None);
+        assert_eq!(TwapAccumulator::new(1000, pdec!(-2), 10), None);
+    }
+
+    #[test]
+    fn test_update_invalid() {
+        let mut acc = accumulator();
+        assert_eq!(acc.update(1199, pdec!(4)), None);
+        assert_eq!(acc.update(1300, pdec!(0)), None);
+        assert_eq!(acc, accumulator());
+    }
+
+    #[test]
+    fn test_update_same_timestamp() {
+        let mut acc = accumulator();
+        acc.update(1200, pdec!(16)).unwrap();
+        assert_eq!(acc.observations().unwrap().len(), 3);
+        assert_eq!(acc.price(), pdec!(16));
+        assert_eq!(acc.latest(), accumulator().latest());
+    }
+
+    #[test]
+    fn test_observations() {
+        let acc = accumulator();
+        assert_eq!(
+            acc.observations()
+                .unwrap()
+                .iter()
+                .map(|o| (o.timestamp, o.price_cumulative))
+                .collect::<Vec<_>>(),
+            vec![(1000, pdec!(0)), (1100, pdec!(200)), (1200, pdec!(1000))]
+        );
+        assert_eq!(
+            acc.latest().unwrap().ln_price_cumulative,
+            pdec!(2).ln().unwrap() * pdec!(100) + pdec!(8).ln().unwrap() * pdec!(100)
+        );
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let mut acc = TwapAccumulator::new(0, pdec!(1), 3).unwrap();
+        for i in 1..=5 {
+            acc.update(i * 10, pdec!(1)).unwrap();
+        }
+        assert_eq!(
+            acc.observations()
+                .unwrap()
+                .iter()
+                .map(|o| o.timestamp)
+                .collect::<Vec<_>>(),
+            vec![30, 40, 50]
+        );
+        assert_eq!(acc.oldest().unwrap().timestamp, 30);
+        assert_eq!(acc.latest().unwrap().timestamp, 50);
+        assert_eq!(acc.observe(29), None);
+        assert_eq!(acc.observe(35).unwrap().price_cumulative, pdec!(35));
+    }
+
+    #[test]
+    fn test_inconsistent_state() {
+        // decoded state is not validated, an out of range index must not panic
+        let mut acc = accumulator();
+        acc.newest = 7;
+        assert_eq!(acc.latest(), None);
+        assert_eq!(acc.observe(1150), None);
+        assert_eq!(acc.update(1300, pdec!(4)), None);
+        acc.observations.clear();
+        assert_eq!(acc.oldest(), None);
+        assert_eq!(acc.observations(), Some(vec![]));
+        assert_eq!(acc.arithmetic_twap(1000, 1200), None);
+    }
+
+    #[test]
+    fn test_observe() {
+        let acc = accumulator();
+        assert_eq!(acc.observe(999), None);
+        assert_eq!(acc.observe(1000).unwrap().price_cumulative, pdec!(0));
+        assert_eq!(acc.observe(1050).unwrap().price_cumulative, pdec!(100));
+        assert_eq!(acc.observe(1100).unwrap().price_cumulative, pdec!(200));
+        assert_eq!(acc.observe(1150).unwrap().price_cumulative, pdec!(600));
+        assert_eq!(acc.observe(1300).unwrap().price_cumulative, pdec!(1400));
+    }
+
+    #[test]
+    fn test_arithmetic_twap() {
+        let acc = accumulator();
+        assert_eq!(acc.arithmetic_twap(1000, 1200).unwrap().value, pdec!(5));
+        assert_eq!(acc.arithmetic_twap(1050, 1150).unwrap().value, pdec!(5));
+        assert_eq!(
+            acc.arithmetic_twap(1000, 1300).unwrap().value,
+            pdec!("4.666666666666666666666666666666666666")
+        );
+        assert_eq!(acc.arithmetic_twap(1200, 1300).unwrap().value, pdec!(4));
+    }
+
+    #[test]
+    fn test_twap_unaligned_window() {
+        // prices using all 36 decimals, the window ends between observations
+        let first = pdec!("0.333333333333333333333333333333333333");
+        let second = pdec!("2.718281828459045235360287471352662497");
+        let mut acc = TwapAccumulator::new(1000, first, 10).unwrap();
+        acc.update(1007, second).unwrap();
+        acc.update(1013, pdec!(5)).unwrap();
+
+        let from = acc.observe(1003).unwrap();
+        let to = acc.observe(1011).unwrap();
+        assert_eq!(from.price_cumulative, first * pdec!(3));
+        assert_eq!(to.price_cumulative, first * pdec!(7) + second * pdec!(4));
+        assert_eq!(
+            to.ln_price_cumulative,
+            first.ln().unwrap() * pdec!(7) + second.ln().unwrap() * pdec!(4)
+        );
+
+        let twap = acc.arithmetic_twap(1003, 1011).unwrap();
+        let sum = (first + second) * pdec!(4);
+        let error = (twap.value * pdec!(8) - sum).checked_abs().unwrap();
+        assert!(error <= twap.max_error * pdec!(8));
+
+        let twap = acc.geometric_twap(1003, 1011).unwrap();
+        let target_result = (first * second).checked_sqrt().unwrap();
+        let error = (twap.value - target_result).checked_abs().unwrap();
+        assert!(error <= twap.max_error);
+    }
+
+    #[test]
+    fn test_twap_invalid_window() {
+        let acc = accumulator();
+        assert_eq!(acc.arithmetic_twap(1100, 1100), None);
+        assert_eq!(acc.arithmetic_twap(1200, 1100), None);
+        assert_eq!(acc.geometric_twap(900, 1100), None);
+    }
+
+    #[test_case(1000, 1200, pdec!(4); "two prices")]
+    #[test_case(1050, 1150, pdec!(4); "interpolated")]
+    #[test_case(1000, 1300, pdec!(4); "extrapolated")]
+    #[test_case(1100, 1200, pdec!(8); "single price")]
+    fn test_geometric_twap(start: i64, end: i64, target_result: PreciseDecimal) {
+        let twap = accumulator().geometric_twap(start, end).unwrap();
+        let error = (twap.value - target_result).checked_abs().unwrap();
+        assert!(error <= twap.max_error);
+        assert!(twap.max_error < pdec!("0.00000000000000005"));
+    }
+
+    #[test]
+    fn test_geometric_twap_large_price() {
+        let mut acc = TwapAccumulator::new(0, pdec!(1000000000000), 10).unwrap();
+        acc.update(100, pdec!(4000000000000)).unwrap();
+        let twap = acc.geometric_twap(0, 200).unwrap();
+        let error = (twap.value - pdec!(2000000000000)).checked_abs().unwrap();
+        assert!(error <= twap.max_error);
+        assert!(twap.max_error < pdec!("0.00001"));
+    }
+
+    #[test]
+    fn test_sbor_roundtrip() {
+        let acc = accumulator();
+        let encoded = scrypto_encode(&acc).unwrap();
+        let decoded: TwapAccumulator = scrypto_decode(&encoded).unwrap();
+        assert_eq!(decoded, acc);
+    }
+}
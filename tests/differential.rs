@@ -0,0 +1,369 @@
+// Differential tests against an arbitrary precision reference
+//
+// Method:
+//      Inputs are sampled with a deterministic SplitMix64 generator, the results of exp, ln, log2,
+//      log10, log_base and pow are compared to the same functions evaluated with 100 decimals (see
+//      src/bin/remez/fixed.rs) and the difference must not exceed the maximum error given by error_exp,
+//      error_ln and error_pow of the crate:
+//              exp(x)          2^(k - 59)                          k = round(x / ln2)
+//              ln(x)           2^-58.45
+//              log2(x)         2^-58.45 / ln2
+//              log10(x)        2^-58.45 / ln10
+//              log_base(x, b)  2^-58.45 * (1 + |log_b(x)|) / (|ln(b)| - 2^-58.45)
+//              pow(x, y)       error_exp(y*ln(x)) + x^y * 2^-58.45 * |y|
+//      On top of the documented bounds every function may lose a few ulps of 10^-36 by truncating its
+//      intermediate results, which scale with 2^k = error_exp * 2^59 for exp and pow. Decimal results are calculated with
+//      PreciseDecimal and narrowed towards zero, losing less than 10^-18 on top.
+//
+// Samples:
+//      Every function is tested with 1000 samples. The ignored *_exhaustive tests run 1000000 samples per
+//      function and are part of CI:
+//              cargo test --release --test differential -- --ignored
+
+#[path = "../src/bin/remez/fixed.rs"]
+mod fixed;
+
+use fixed::Fixed;
+use radix_common::math::{Decimal, PreciseDecimal, I192, I256};
+use scrypto_math::{
+    error_exp, error_ln, error_pow, ExponentialPreciseDecimal, LogarithmDecimal,
+    LogarithmPreciseDecimal, PowerPreciseDecimal,
+};
+
+const SAMPLES: usize = 1000;
+const EXHAUSTIVE_SAMPLES: usize = 1_000_000;
+const SEED: u64 = 0x5c29_7b0a_e4d1_3f68;
+const ULPS: i64 = 4; // truncation of intermediate results
+
+/// SplitMix64 pseudo random number generator
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_u128(&mut self) -> u128 {
+        (u128::from(self.next()) << 64) | u128::from(self.next())
+    }
+
+    /// Uniform integer in [lower, upper]
+    fn range(&mut self, lower: i64, upper: i64) -> i64 {
+        lower + (self.next() % (upper - lower + 1) as u64) as i64
+    }
+
+    /// Uniform PreciseDecimal in [lower, upper) with all 36 decimals random
+    fn uniform(&mut self, lower: i64, upper: i64) -> PreciseDecimal {
+        let one = PreciseDecimal::ONE.precise_subunits();
+        let width = I256::from(upper - lower) * one;
+        let offset = I256::from(self.next_u128()) % width;
+        PreciseDecimal::from_precise_subunits(I256::from(lower) * one + offset)
+    }
+
+    /// Positive PreciseDecimal with log-uniformly distributed magnitude between 10^-36 and 2^254
+    fn positive(&mut self) -> PreciseDecimal {
+        let mantissa = self.next_u128() >> self.range(0, 127);
+        let subunits = (I256::from(mantissa) << self.range(0, 126) as u32).max(I256::ONE);
+        PreciseDecimal::from_precise_subunits(subunits)
+    }
+
+    /// Positive Decimal with log-uniformly distributed magnitude between 10^-18 and 2^189
+    fn positive_decimal(&mut self) -> Decimal {
+        let mantissa = self.next_u128() >> self.range(0, 127);
+        let attos = (I192::from(mantissa) << self.range(0, 62) as u32).max(I192::ONE);
+        Decimal::from_attos(attos)
+    }
+}
+
+fn fixed(value: PreciseDecimal) -> Fixed {
+    Fixed::parse(&value.to_string()).unwrap()
+}
+
+fn constant(value: &str) -> Fixed {
+    Fixed::parse(value).unwrap()
+}
+
+fn ulp() -> Fixed {
+    constant("0.000000000000000000000000000000000001")
+}
+
+/// Truncation of narrowing a PreciseDecimal result to Decimal
+fn error_narrowing() -> Fixed {
+    constant("0.000000000000000001")
+}
+
+/// 2^exponent
+fn two_pow(exponent: i64) -> Fixed {
+    let one = Fixed::one();
+    if exponent < 0 {
+        Fixed(one.0 >> exponent.unsigned_abs() as usize)
+    } else {
+        Fixed(one.0 << exponent as usize)
+    }
+}
+
+/// Truncation of intermediate results of exp, scaled with 2^k = error_exp(x) * 2^59
+fn truncation(error_exp: PreciseDecimal) -> Fixed {
+    &(&(&fixed(error_exp) * &two_pow(59)) + &Fixed::one()) * &(&ulp() * &Fixed::from_int(ULPS))
+}
+
+/// Maximum error of exp plus the truncation of intermediate results
+fn max_error_exp(x: PreciseDecimal) -> Fixed {
+    let error = error_exp(x).unwrap();
+    &fixed(error) + &truncation(error)
+}
+
+/// Maximum error of pow plus the truncation of intermediate results
+fn max_error_pow(x: PreciseDecimal, y: PreciseDecimal) -> Fixed {
+    let argument = x.checked_abs().unwrap().ln().unwrap() * y;
+    &fixed(error_pow(x, y).unwrap()) + &truncation(error_exp(argument).unwrap())
+}
+
+/// Asserts that `result` lies within `max_error` of `reference`
+fn assert_within(
+    name: &str,
+    input: String,
+    result: PreciseDecimal,
+    reference: &Fixed,
+    max_error: &Fixed,
+) {
+    let error = (&fixed(result) - reference).abs();
+    assert!(
+        &error <= max_error,
+        "{name}({input}) = {result}, reference {reference}, error {error} > {max_error}"
+    );
+}
+
+fn check_exp(samples: usize) {
+    let mut random = Random(SEED);
+    for i in 0..samples {
+        // every other sample close to zero, where 2^k is small
+        let x = if i % 2 == 0 {
+            random.uniform(-82, 93)
+        } else {
+            random.uniform(-2, 2)
+        };
+        let result = x.exp().unwrap();
+        let reference = fixed::exp(&fixed(x));
+        assert_within("exp", x.to_string(), result, &reference, &max_error_exp(x));
+    }
+}
+
+fn check_ln_log2_log10(samples: usize) {
+    let mut random = Random(SEED);
+    let error_ln = &fixed(error_ln()) + &(&ulp() * &Fixed::from_int(ULPS));
+    let ln10 = fixed::ln(&Fixed::from_int(10));
+    for _ in 0..samples {
+        let x = random.positive();
+        let reference = fixed::ln(&fixed(x));
+        assert_within("ln", x.to_string(), x.ln().unwrap(), &reference, &error_ln);
+        assert_within(
+            "log2",
+            x.to_string(),
+            x.log2().unwrap(),
+            &(&reference / &fixed::ln2()),
+            &(&(&error_ln / &fixed::ln2()) + &ulp()),
+        );
+        assert_within(
+            "log10",
+            x.to_string(),
+            x.log10().unwrap(),
+            &(&reference / &ln10),
+            &(&(&error_ln / &ln10) + &ulp()),
+        );
+    }
+}
+
+fn check_log_base(samples: usize) {
+    let mut random = Random(SEED);
+    let error_ln = &fixed(error_ln()) + &(&ulp() * &Fixed::from_int(ULPS));
+    for _ in 0..samples {
+        let x = random.positive();
+        let base = random.positive();
+        let ln_base = fixed::ln(&fixed(base));
+        if ln_base.abs() <= error_ln {
+            // the logarithm of the base cannot be separated from zero
+            continue;
+        }
+        let reference = &fixed::ln(&fixed(x)) / &ln_base;
+        let max_error = &(&(&error_ln * &(&Fixed::one() + &reference.abs()))
+            / &(&ln_base.abs() - &error_ln))
+            + &ulp();
+        let result = x.log_base(base).unwrap();
+        assert_within(
+            "log_base",
+            format!("{x}, {base}"),
+            result,
+            &reference,
+            &max_error,
+        );
+    }
+}
+
+fn check_decimal_logarithms(samples: usize) {
+    let mut random = Random(SEED);
+    let error_ln = &fixed(error_ln()) + &(&ulp() * &Fixed::from_int(ULPS));
+    let ln10 = fixed::ln(&Fixed::from_int(10));
+    for _ in 0..samples {
+        let x = random.positive_decimal();
+        let reference = fixed::ln(&fixed(x.into()));
+        assert_within(
+            "ln",
+            x.to_string(),
+            PreciseDecimal::from(x.ln().unwrap()),
+            &reference,
+            &(&error_ln + &error_narrowing()),
+        );
+        assert_within(
+            "log2",
+            x.to_string(),
+            PreciseDecimal::from(x.log2().unwrap()),
+            &(&reference / &fixed::ln2()),
+            &(&(&(&error_ln / &fixed::ln2()) + &ulp()) + &error_narrowing()),
+        );
+        assert_within(
+            "log10",
+            x.to_string(),
+            PreciseDecimal::from(x.log10().unwrap()),
+            &(&reference / &ln10),
+            &(&(&(&error_ln / &ln10) + &ulp()) + &error_narrowing()),
+        );
+
+        let base = random.positive_decimal();
+        let ln_base = fixed::ln(&fixed(base.into()));
+        if ln_base.abs() <= error_ln {
+            // the logarithm of the base cannot be separated from zero
+            continue;
+        }
+        let reference = &reference / &ln_base;
+        let max_error = &(&(&error_ln * &(&Fixed::one() + &reference.abs()))
+            / &(&ln_base.abs() - &error_ln))
+            + &(&ulp() + &error_narrowing());
+        assert_within(
+            "log_base",
+            format!("{x}, {base}"),
+            PreciseDecimal::from(x.log_base(base).unwrap()),
+            &reference,
+            &max_error,
+        );
+    }
+}
+
+fn check_pow(samples: usize) {
+    let mut random = Random(SEED);
+    let mut tested = 0;
+    while tested < samples {
+        let x = random.positive();
+        let y = random.uniform(-20, 20);
+        let ln_x = fixed::ln(&fixed(x));
+        let argument = &ln_x * &fixed(y);
+        if argument > Fixed::from_int(90) {
+            // not representable
+            continue;
+        }
+        let reference = fixed::exp(&argument);
+        assert_within(
+            "pow",
+            format!("{x}, {y}"),
+            x.pow(y).unwrap(),
+            &reference,
+            &max_error_pow(x, y),
+        );
+        tested += 1;
+    }
+}
+
+fn check_pow_negative_base(samples: usize) {
+    let mut random = Random(SEED);
+    let mut tested = 0;
+    while tested < samples {
+        let x = random.positive();
+        let y = random.range(-20, 20);
+        let ln_x = fixed::ln(&fixed(x));
+        let argument = &ln_x * &Fixed::from_int(y);
+        if argument > Fixed::from_int(90) {
+            continue;
+        }
+        let magnitude = fixed::exp(&argument);
+        let reference = if y % 2 == 0 {
+            magnitude.clone()
+        } else {
+            -&magnitude
+        };
+        let y = PreciseDecimal::from(y);
+        let result = (-x).pow(y).unwrap();
+        let max_error = max_error_pow(-x, y);
+        assert_within("pow", format!("-{x}, {y}"), result, &reference, &max_error);
+        tested += 1;
+    }
+}
+
+#[test]
+fn test_exp() {
+    check_exp(SAMPLES);
+}
+
+#[test]
+fn test_ln_log2_log10() {
+    check_ln_log2_log10(SAMPLES);
+}
+
+#[test]
+fn test_log_base() {
+    check_log_base(SAMPLES);
+}
+
+#[test]
+fn test_decimal_logarithms() {
+    check_decimal_logarithms(SAMPLES);
+}
+
+#[test]
+fn test_pow() {
+    check_pow(SAMPLES);
+}
+
+#[test]
+fn test_pow_negative_base() {
+    check_pow_negative_base(SAMPLES);
+}
+
+#[test]
+#[ignore = "exhaustive, run with --release -- --ignored"]
+fn test_exp_exhaustive() {
+    check_exp(EXHAUSTIVE_SAMPLES);
+}
+
+#[test]
+#[ignore = "exhaustive, run with --release -- --ignored"]
+fn test_ln_log2_log10_exhaustive() {
+    check_ln_log2_log10(EXHAUSTIVE_SAMPLES);
+}
+
+#[test]
+#[ignore = "exhaustive, run with --release -- --ignored"]
+fn test_log_base_exhaustive() {
+    check_log_base(EXHAUSTIVE_SAMPLES);
+}
+
+#[test]
+#[ignore = "exhaustive, run with --release -- --ignored"]
+fn test_decimal_logarithms_exhaustive() {
+    check_decimal_logarithms(EXHAUSTIVE_SAMPLES);
+}
+
+#[test]
+#[ignore = "exhaustive, run with --release -- --ignored"]
+fn test_pow_exhaustive() {
+    check_pow(EXHAUSTIVE_SAMPLES);
+}
+
+#[test]
+#[ignore = "exhaustive, run with --release -- --ignored"]
+fn test_pow_negative_base_exhaustive() {
+    check_pow_negative_base(EXHAUSTIVE_SAMPLES);
+}
@@ -0,0 +1,133 @@
+// Property based tests for exp, ln and pow
+//
+// Properties:
+//      1. exp(a + b) = exp(a) * exp(b)
+//      2. ln(a * b)  = ln(a) + ln(b)
+//      3. pow(x, a + b) = pow(x, a) * pow(x, b)
+//      4. pow(-x, n) = (-1)^n * pow(x, n) for integers n, and None for non-integer exponents
+//      5. exp, ln and pow(., y > 0) are monotonically increasing
+//
+// Tolerance:
+//      Both sides are compared within the sum of their maximum errors given by error_exp, error_ln and
+//      error_pow of the crate:
+//              error_exp(x)    = 2^(k - 59)                        k = round(x / ln2)
+//              error_ln        = 2^-58.45
+//              error_pow(x, y) = error_exp(y*ln(x)) + x^y * error_ln * |y|
+//      For products the errors of the factors are scaled by the other factor, e.g.
+//              |exp'(a)*exp'(b) - exp(a)*exp(b)| <= exp(a)*error_exp(b) + exp(b)*error_exp(a) + error_exp(a)*error_exp(b)
+//      Truncation of intermediate results adds a few ulps of 10^-36 (scaled with 2^k = error_exp * 2^59
+//      for exp and pow).
+
+use proptest::prelude::*;
+use radix_common::math::{PreciseDecimal, I256};
+use radix_common_derive::pdec;
+use scrypto_math::{
+    error_exp, error_ln, error_pow, ExponentialPreciseDecimal, LogarithmPreciseDecimal,
+    PowerPreciseDecimal,
+};
+
+const ULPS: PreciseDecimal = pdec!("0.000000000000000000000000000000000004"); // truncation of intermediate results
+const TWO_POW_59: PreciseDecimal = pdec!(576460752303423488);
+
+/// PreciseDecimal uniformly distributed in [lower, upper] with all 36 decimals random
+fn uniform(lower: i64, upper: i64) -> impl Strategy<Value = PreciseDecimal> {
+    let one = 10i128.pow(36);
+    (i128::from(lower) * one..=i128::from(upper) * one)
+        .prop_map(|subunits| PreciseDecimal::from_precise_subunits(I256::from(subunits)))
+}
+
+/// Positive PreciseDecimal with log-uniformly distributed magnitude in [10^min_exponent, 10^max_exponent)
+fn positive(min_exponent: i32, max_exponent: i32) -> impl Strategy<Value = PreciseDecimal> {
+    (10u64.pow(17)..10u64.pow(18), min_exponent..max_exponent).prop_map(|(mantissa, exponent)| {
+        // mantissa * 10^(exponent - 17) in subunits of 10^-36
+        let scale = (0..exponent + 19).fold(I256::ONE, |scale, _| scale * I256::from(10));
+        let subunits = I256::from(mantissa) * scale;
+        PreciseDecimal::from_precise_subunits(subunits)
+    })
+}
+
+/// Truncation of intermediate results of exp, scaled with 2^k = error_exp(x) * 2^59
+fn truncation(error_exp: PreciseDecimal) -> PreciseDecimal {
+    (error_exp * TWO_POW_59 + PreciseDecimal::ONE) * ULPS
+}
+
+/// Maximum error of exp plus the truncation of intermediate results
+fn max_error_exp(x: PreciseDecimal) -> PreciseDecimal {
+    let error = error_exp(x).unwrap();
+    error + truncation(error)
+}
+
+/// Maximum error of pow for a positive base plus the truncation of intermediate results
+fn max_error_pow(x: PreciseDecimal, y: PreciseDecimal) -> PreciseDecimal {
+    error_pow(x, y).unwrap() + truncation(error_exp(x.ln().unwrap() * y).unwrap())
+}
+
+fn assert_close(left: PreciseDecimal, right: PreciseDecimal, max_error: PreciseDecimal) {
+    let error = (left - right).checked_abs().unwrap();
+    assert!(
+        error <= max_error,
+        "{left} and {right} differ by {error} > {max_error}"
+    );
+}
+
+proptest! {
+    #[test]
+    fn test_exp_sum(a in uniform(-40, 40), b in uniform(-40, 40)) {
+        let (exp_a, exp_b) = (a.exp().unwrap(), b.exp().unwrap());
+        let (error_a, error_b) = (max_error_exp(a), max_error_exp(b));
+        let max_error = max_error_exp(a + b) + exp_a * error_b + exp_b * error_a + error_a * error_b + ULPS;
+        assert_close((a + b).exp().unwrap(), exp_a * exp_b, max_error);
+    }
+
+    #[test]
+    fn test_ln_product(a in positive(-9, 9), b in positive(-9, 9)) {
+        // the truncation of a*b changes ln by at most 10^-36 / (a*b)
+        let product = a * b;
+        let max_error = error_ln() * pdec!(3) + ULPS + ULPS / product;
+        assert_close(product.ln().unwrap(), a.ln().unwrap() + b.ln().unwrap(), max_error);
+    }
+
+    #[test]
+    fn test_pow_sum(x in positive(-2, 2), a in uniform(-5, 5), b in uniform(-5, 5)) {
+        let (pow_a, pow_b) = (x.pow(a).unwrap(), x.pow(b).unwrap());
+        let (error_a, error_b) = (max_error_pow(x, a), max_error_pow(x, b));
+        let max_error = max_error_pow(x, a + b) + pow_a * error_b + pow_b * error_a + error_a * error_b + ULPS;
+        assert_close(x.pow(a + b).unwrap(), pow_a * pow_b, max_error);
+    }
+
+    #[test]
+    fn test_pow_negative_base(x in positive(-2, 2), n in -15i64..=15) {
+        let n = PreciseDecimal::from(n);
+        let pow = (-x).pow(n).unwrap();
+        let magnitude = x.pow(n).unwrap();
+        if n.precise_subunits() % (PreciseDecimal::ONE.precise_subunits() * I256::from(2)) == I256::ZERO {
+            prop_assert_eq!(pow, magnitude);
+        } else {
+            prop_assert_eq!(pow, -magnitude);
+        }
+    }
+
+    #[test]
+    fn test_pow_negative_base_fraction(x in positive(-3, 3), y in uniform(-15, 15)) {
+        prop_assume!(y.precise_subunits() % PreciseDecimal::ONE.precise_subunits() != I256::ZERO);
+        prop_assert_eq!((-x).pow(y), None);
+    }
+
+    #[test]
+    fn test_exp_monotonic(a in uniform(-82, 93), b in uniform(-82, 93)) {
+        let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+        prop_assert!(lower.exp().unwrap() <= upper.exp().unwrap() + max_error_exp(lower) + max_error_exp(upper));
+    }
+
+    #[test]
+    fn test_ln_monotonic(a in positive(-19, 40), b in positive(-19, 40)) {
+        let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+        prop_assert!(lower.ln().unwrap() <= upper.ln().unwrap() + error_ln() * pdec!(2));
+    }
+
+    #[test]
+    fn test_pow_monotonic(a in positive(-3, 3), b in positive(-3, 3), y in uniform(0, 10)) {
+        let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+        prop_assert!(lower.pow(y).unwrap() <= upper.pow(y).unwrap() + max_error_pow(lower, y) + max_error_pow(upper, y));
+    }
+}
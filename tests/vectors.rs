@@ -0,0 +1,124 @@
+// Shared test vectors of the Rust and Python implementations
+//
+// Format:
+//      tests/vectors/vectors.json is generated by python/scryptomath.py and versioned with a top level
+//      "version" field. Every vector consists of
+//              function        exp, ln, log2, log10, log_base or pow
+//              type            Decimal or PreciseDecimal, the type the function is evaluated with
+//              input           arguments as decimal strings
+//              expected        mathematically exact result rounded to 36 decimal places, null if None
+//                              is expected
+//              max_error       documented maximum error including the truncation of intermediate results
+//                              and of the narrowing to Decimal, null if None is expected
+//              result          bit exact result of the Python mirror of the implementation, null if
+//                              None is returned
+//
+// Regenerate:
+//      python python/scryptomath.py tests/vectors/vectors.json
+
+use radix_common::math::{Decimal, PreciseDecimal};
+use scrypto_math::{
+    ExponentialDecimal, ExponentialPreciseDecimal, LogarithmDecimal, LogarithmPreciseDecimal,
+    PowerDecimal, PowerPreciseDecimal,
+};
+use serde::Deserialize;
+
+const VERSION: u32 = 3;
+const VECTORS: &str = include_str!("vectors/vectors.json");
+
+#[derive(Deserialize)]
+struct TestVectors {
+    version: u32,
+    vectors: Vec<TestVector>,
+}
+
+#[derive(Deserialize)]
+struct TestVector {
+    function: String,
+    #[serde(rename = "type")]
+    decimal_type: String,
+    input: Vec<String>,
+    expected: Option<String>,
+    max_error: Option<String>,
+    result: Option<String>,
+}
+
+fn decimal(value: &str) -> Decimal {
+    Decimal::try_from(value).unwrap()
+}
+
+fn precise_decimal(value: &str) -> PreciseDecimal {
+    PreciseDecimal::try_from(value).unwrap()
+}
+
+/// Evaluates the vector with Decimal arguments, widening the result to a PreciseDecimal
+fn evaluate_decimal(function: &str, input: &[Decimal]) -> Option<PreciseDecimal> {
+    let result = match (function, input) {
+        ("exp", [x]) => x.exp(),
+        ("ln", [x]) => x.ln(),
+        ("log2", [x]) => x.log2(),
+        ("log10", [x]) => x.log10(),
+        ("log_base", [x, base]) => x.log_base(*base),
+        ("pow", [x, y]) => x.pow(*y),
+        _ => panic!("unknown function {function} with {} arguments", input.len()),
+    };
+    result.map(PreciseDecimal::from)
+}
+
+fn evaluate_precise_decimal(function: &str, input: &[PreciseDecimal]) -> Option<PreciseDecimal> {
+    match (function, input) {
+        ("exp", [x]) => x.exp(),
+        ("ln", [x]) => x.ln(),
+        ("log2", [x]) => x.log2(),
+        ("log10", [x]) => x.log10(),
+        ("log_base", [x, base]) => x.log_base(*base),
+        ("pow", [x, y]) => x.pow(*y),
+        _ => panic!("unknown function {function} with {} arguments", input.len()),
+    }
+}
+
+fn evaluate(vector: &TestVector) -> Option<PreciseDecimal> {
+    match vector.decimal_type.as_str() {
+        "Decimal" => {
+            let input: Vec<_> = vector.input.iter().map(|x| decimal(x)).collect();
+            evaluate_decimal(&vector.function, &input)
+        }
+        "PreciseDecimal" => {
+            let input: Vec<_> = vector.input.iter().map(|x| precise_decimal(x)).collect();
+            evaluate_precise_decimal(&vector.function, &input)
+        }
+        decimal_type => panic!("unknown decimal type {decimal_type}"),
+    }
+}
+
+#[test]
+fn test_vectors() {
+    let vectors: TestVectors = serde_json::from_str(VECTORS).unwrap();
+    assert_eq!(vectors.version, VERSION, "unsupported test vector version");
+    assert!(!vectors.vectors.is_empty());
+
+    for vector in &vectors.vectors {
+        let name = format!(
+            "{}::{}({})",
+            vector.decimal_type,
+            vector.function,
+            vector.input.join(", ")
+        );
+        let result = evaluate(vector);
+        assert_eq!(
+            result,
+            vector.result.as_deref().map(precise_decimal),
+            "{name} differs from the Python mirror"
+        );
+        let (Some(expected), Some(max_error)) = (&vector.expected, &vector.max_error) else {
+            assert_eq!(result, None, "{name}");
+            continue;
+        };
+        let result = result.unwrap_or_else(|| panic!("{name} returned None, expected {expected}"));
+        let error = (result - precise_decimal(expected)).checked_abs().unwrap();
+        assert!(
+            error <= precise_decimal(max_error),
+            "{name} = {result}, expected {expected}, error {error} > {max_error}"
+        );
+    }
+}